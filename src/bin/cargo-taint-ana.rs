@@ -1,18 +1,26 @@
 //! `cargo taint-ana $FLAGS $ARGS` calls `cargo build` with RUSTC_WRAPPER set to `taint-ana`.
-//! The flags are passed to `taint-ana` through env var `TAINT_ANA_FLAGS`.
+//! The flags are passed to `taint-ana` through env var `TAINT_ANA_FLAGS`, each followed by `\x1f`
+//! so that values containing spaces (paths, `--only` patterns) are kept intact.
 //! The remaining args are unchanged.
 //! To re-run `cargo taint-ana` with different flags on the same crate, please `cargo clean` first.
 use std::env;
 use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+// Shared with the driver so that invalid flags are rejected before `cargo build` starts.
+#[allow(dead_code)]
+#[path = "../options.rs"]
+mod options;
+
 const CARGO_TAINT_ANA_HELP: &str = r#"Extract function signatures from Rust project
 Usage:
     cargo taint-ana [options] [--] [<cargo build options>...]
 Common options:
     -h, --help               Print this message
     -V, --version            Print version info and exit
-    
+
+{OPTIONS}
 Options after the first "--" are the same arguments that `cargo build` accepts.
 
 Examples:
@@ -20,10 +28,119 @@ Examples:
     cargo taint-ana
     # With specific target
     cargo +nightly taint-ana -- --target x86_64-unknown-linux-gnu
+    # Only analyze functions of one module, with a more precise DFS
+    cargo taint-ana --only '^my_crate::parser::' --k 3 --max-visits 20
 "#;
 
 fn show_help() {
-    println!("{}", CARGO_TAINT_ANA_HELP);
+    println!("{}", CARGO_TAINT_ANA_HELP.replace("{OPTIONS}", options::OPTIONS_HELP));
+}
+
+/// Make the `--output` and `--config` paths absolute (the driver runs in each package's
+/// directory) and truncate the output file once; the driver appends the reports of every crate to it.
+fn prepare_output(flags: &mut [String]) {
+    const PATH_FLAGS: [&str; 2] = ["--output", "--config"];
+    let cwd = env::current_dir().unwrap_or_default();
+    let mut iter = flags.iter_mut();
+    while let Some(flag) = iter.next() {
+        let (name, path) = if let Some(name) = PATH_FLAGS.into_iter().find(|name| flag == name) {
+            match iter.next() {
+                Some(value) => (name, value),
+                None => return,
+            }
+        } else if let Some(name) = PATH_FLAGS.into_iter().find(|name| flag.starts_with(&format!("{}=", name))) {
+            (name, flag)
+        } else {
+            continue;
+        };
+        let prefix = format!("{}=", name);
+        let (prefix, value) = match path.strip_prefix(prefix.as_str()) {
+            Some(value) => (prefix.as_str(), value.to_string()),
+            None => ("", path.clone()),
+        };
+        let absolute: PathBuf = cwd.join(value);
+        if name == "--output" {
+            if let Err(e) = std::fs::File::create(&absolute) {
+                eprintln!("error: cannot create output file {}: {}", absolute.display(), e);
+                std::process::exit(1);
+            }
+        }
+        *path = format!("{}{}", prefix, absolute.display());
+    }
+}
+
+/// Create an empty directory for the per-crate SARIF logs of this run.
+fn prepare_sarif_dir() -> PathBuf {
+    let dir = env::temp_dir().join(format!("taint-ana-sarif-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        eprintln!("error: cannot create {}: {}", dir.display(), e);
+        std::process::exit(1);
+    }
+    dir
+}
+
+/// Functions whose analysis was incomplete (see `report::IncompleteCoverage` in the driver)
+const NOTIFICATIONS: &str = "/runs/0/invocations/0/toolExecutionNotifications";
+
+/// Merge the per-crate SARIF logs into one log with a single run.
+/// All logs come from the same driver, so the tool section of the first one is kept
+/// and the results and tool notifications of the others are appended to it.
+fn merge_sarif_logs(logs: Vec<serde_json::Value>) -> serde_json::Value {
+    let mut logs = logs.into_iter();
+    let Some(mut merged) = logs.next() else {
+        return serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{ "tool": { "driver": { "name": "taint-ana" } }, "results": [] }],
+        });
+    };
+    for log in logs {
+        let results = log["runs"][0]["results"].as_array().cloned().unwrap_or_default();
+        if let Some(merged_results) = merged["runs"][0]["results"].as_array_mut() {
+            merged_results.extend(results);
+        }
+        let notifications = log
+            .pointer(NOTIFICATIONS)
+            .and_then(|notifications| notifications.as_array().cloned())
+            .unwrap_or_default();
+        if let Some(merged_notifications) = merged.pointer_mut(NOTIFICATIONS).and_then(|n| n.as_array_mut()) {
+            merged_notifications.extend(notifications);
+        }
+    }
+    merged
+}
+
+/// Read the per-crate SARIF logs from `dir`, write the merged log to `output` (stdout when
+/// unset) and remove `dir`.
+fn write_merged_sarif(dir: &Path, output: Option<&Path>) {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect())
+        .unwrap_or_default();
+    paths.sort();
+    let logs = paths
+        .iter()
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(path).ok()?;
+            match serde_json::from_str(&content) {
+                Ok(log) => Some(log),
+                Err(e) => {
+                    eprintln!("warning: ignoring invalid SARIF log {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+    let merged = serde_json::to_string_pretty(&merge_sarif_logs(logs)).expect("SARIF log is valid JSON");
+    match output {
+        Some(output) => {
+            if let Err(e) = std::fs::write(output, merged) {
+                eprintln!("error: cannot write {}: {}", output.display(), e);
+            }
+        }
+        None => println!("{}", merged),
+    }
+    let _ = std::fs::remove_dir_all(dir);
 }
 
 fn show_version() {
@@ -116,9 +233,22 @@ fn in_cargo_taint_ana() {
 
     let mut args = std::env::args().skip(2);
 
-    let flags: Vec<_> = args.by_ref().take_while(|arg| arg != "--").collect();
-    let flags = flags.join(" ");
-    cmd.env("TAINT_ANA_FLAGS", flags);
+    let mut flags: Vec<_> = args.by_ref().take_while(|arg| arg != "--").collect();
+    let options = match options::Options::parse(&flags) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    prepare_output(&mut flags);
+    cmd.env("TAINT_ANA_FLAGS", options::join_flags(&flags));
+
+    // SARIF: every crate writes its own log, merged into one file below
+    let sarif_dir = (options.format == options::OutputFormat::Sarif).then(prepare_sarif_dir);
+    if let Some(dir) = &sarif_dir {
+        cmd.env(options::SARIF_DIR_ENV, dir);
+    }
 
     let exit_status = cmd
         .args(args)
@@ -126,6 +256,11 @@ fn in_cargo_taint_ana() {
         .expect("could not run cargo")
         .wait()
         .expect("failed to wait for cargo?");
+    if let Some(dir) = &sarif_dir {
+        let cwd = env::current_dir().unwrap_or_default();
+        let output = options.output.as_ref().map(|output| cwd.join(output));
+        write_merged_sarif(dir, output.as_deref());
+    }
     if !exit_status.success() {
         std::process::exit(exit_status.code().unwrap_or(-1))
     };
@@ -145,3 +280,43 @@ fn main() {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 测试1: 合并多个 crate 的 SARIF 日志
+    #[test]
+    fn test_merge_sarif_logs() {
+        let log = |rule: &str| json!({
+            "version": "2.1.0",
+            "runs": [{
+                "tool": { "driver": { "name": "taint-ana" } },
+                "invocations": [{ "toolExecutionNotifications": [{ "message": { "text": rule } }] }],
+                "results": [{ "ruleId": rule }],
+            }],
+        });
+        let merged = merge_sarif_logs(vec![log("use-after-drop"), log("tainted-sink")]);
+        let results = merged["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(merged["runs"].as_array().unwrap().len(), 1);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1]["ruleId"], "tainted-sink");
+        assert_eq!(merged.pointer(NOTIFICATIONS).unwrap().as_array().unwrap().len(), 2);
+
+        let empty = merge_sarif_logs(Vec::new());
+        assert!(empty["runs"][0]["results"].as_array().unwrap().is_empty());
+    }
+
+    /// 测试2: 相对的 --config 路径按当前目录转为绝对路径
+    #[test]
+    fn test_prepare_output_config_path() {
+        let cwd = env::current_dir().unwrap();
+        let mut flags: Vec<String> =
+            ["--config", "conf/taint-ana.toml", "--k", "3", "--config=other.toml"].iter().map(|s| s.to_string()).collect();
+        prepare_output(&mut flags);
+        assert_eq!(flags[1], cwd.join("conf/taint-ana.toml").display().to_string());
+        assert_eq!(flags[3], "3");
+        assert_eq!(flags[4], format!("--config={}", cwd.join("other.toml").display()));
+    }
+}
@@ -1,29 +1,38 @@
 //! MIR analysis callbacks for rustc plugin system.
-//! Detects use-after-drop, double-drop, and ownership violations.
+//! Detects use-after-drop, double-drop, ownership violations and (opt-in) memory leaks.
+extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_hir;
 
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use log::debug;
+use rustc_data_structures::sync::par_map;
 use rustc_driver::Compilation;
-use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 use rustc_interface::interface;
 use rustc_middle::mir::Body;
 use rustc_middle::mir::mono::MonoItem;
 use rustc_middle::ty;
-use rustc_middle::ty::{Instance, TyCtxt, TypingEnv};
+use rustc_middle::ty::{Instance, InstanceKind, TyCtxt, TypeVisitableExt, TypingEnv};
+
+use crate::options::Options;
 
 pub struct TaintAnaCallbacks {
     file_name: String,
     output_directory: PathBuf,
+    /// Options parsed from `TAINT_ANA_FLAGS`
+    options: Options,
 }
 
 impl TaintAnaCallbacks {
-    pub fn new() -> Self {
+    pub fn new(options: Options) -> Self {
         Self {
             file_name: String::new(),
             output_directory: PathBuf::default(),
+            options,
         }
     }
 }
@@ -83,6 +92,8 @@ impl TaintAnaCallbacks {
         
         let typing_env = TypingEnv::fully_monomorphized();
         let mut analyzed_count = 0;
+        let mut candidates = Vec::new();
+        let mut instances = Vec::new();
         
         // Process each function definition
         for &local_def_id in mir_keys.iter() {
@@ -108,6 +119,12 @@ impl TaintAnaCallbacks {
                 if file_path.contains("\\target\\") || file_path.contains("/target/") {
                     continue;
                 }
+                
+                // Honour include/exclude globs from taint-ana.toml
+                if !crate::config::get().is_path_included(&file_path) {
+                    debug!("Skipping {} excluded by [paths] ({})", tcx.def_path_str(def_id), file_path);
+                    continue;
+                }
             }
             
             // Check if this is a function (not a const, static, etc.)
@@ -117,19 +134,48 @@ impl TaintAnaCallbacks {
                 continue;
             }
             
-            // Directly use optimized_mir instead of trying to resolve instances
-            // This avoids issues with generic parameters
-            let body = tcx.optimized_mir(def_id);
+            // Honour --only / --skip
+            if !self.options.is_function_selected(&tcx.def_path_str(def_id)) {
+                continue;
+            }
+            
+            candidates.push(def_id);
+        }
+        
+        // Concrete instantiations of local functions that were actually collected for codegen
+        let mut mono_instances: HashMap<DefId, Vec<Instance<'tcx>>> = HashMap::new();
+        for cgu in tcx.collect_and_partition_mono_items(()).codegen_units {
+            for mono_item in cgu.items().keys() {
+                if let MonoItem::Fn(instance) = mono_item {
+                    if matches!(instance.def, InstanceKind::Item(_)) && instance.def_id().is_local() {
+                        let entry = mono_instances.entry(instance.def_id()).or_default();
+                        if !entry.contains(instance) {
+                            entry.push(*instance);
+                        }
+                    }
+                }
+            }
+        }
+        
+        for def_id in candidates {
+            if let Some(concrete) = mono_instances.remove(&def_id) {
+                instances.extend(concrete);
+                continue;
+            }
+            
+            if tcx.generics_of(def_id).requires_monomorphization(tcx) {
+                // Generic function without any local instantiation (e.g. library code):
+                // analyze the identity-substituted (polymorphic) body instead
+                let args = ty::GenericArgs::identity_for_item(tcx, def_id);
+                debug!("No instantiation collected for {}, analyzing polymorphic body", tcx.def_path_str(def_id));
+                instances.push(Instance::new_raw(def_id, args));
+                continue;
+            }
             
             // Create a simple instance for reporting purposes
             match Instance::try_resolve(tcx, typing_env, def_id, ty::List::empty()) {
-                Ok(Some(instance)) => {
-                    analyze_function(tcx, instance, body);
-                    analyzed_count += 1;
-                }
+                Ok(Some(instance)) => instances.push(instance),
                 Ok(None) => {
-                    // For items we can't resolve (e.g., generic functions without substitution),
-                    // we can still analyze them using a monomorphic instance
                     debug!("Could not resolve instance for: {}, skipping", tcx.def_path_str(def_id));
                 }
                 Err(e) => {
@@ -138,7 +184,101 @@ impl TaintAnaCallbacks {
             }
         }
         
+        let mut callgraph = crate::callgraph::CallGraph::new();
+        callgraph.analyze(instances, tcx, typing_env);
+        self.dump_callgraph(&callgraph, tcx, &crate_name);
+        
+        // Analyze callees before callers so that their summaries are available at call sites.
+        // Functions of the same level do not call each other and are analyzed concurrently
+        // (`--jobs`); results are reported level by level, sorted by function name, so the
+        // output does not depend on the number of threads.
+        let mut summaries = crate::summary::SummaryMap::new();
+        let mut findings = Vec::new();
+        let mut incomplete = Vec::new();
+        let dfs_config = get_dfs_config();
+        for level in callgraph.bottom_up_levels() {
+            let (recursive, independent): (Vec<_>, Vec<_>) = level
+                .into_iter()
+                .partition(|scc| callgraph.is_recursive(scc));
+            let independent: Vec<Instance<'tcx>> = independent
+                .iter()
+                .map(|scc| *callgraph.graph[scc[0]].instance())
+                .collect();
+            let mut results: Vec<FunctionAnalysis<'tcx>> =
+                par_map(independent, |instance| analyze_function(tcx, instance, &summaries));
+            for result in &results {
+                summaries.insert(result.instance, result.summary.clone());
+            }
+            
+            // Members of a recursive cycle see the summaries of the members analyzed before them
+            for scc in recursive {
+                for idx in scc {
+                    let instance = *callgraph.graph[idx].instance();
+                    debug!("{} is part of a recursive cycle, summaries within the cycle may be incomplete",
+                           tcx.def_path_str(instance.def_id()));
+                    let result = analyze_function(tcx, instance, &summaries);
+                    summaries.insert(instance, result.summary.clone());
+                    results.push(result);
+                }
+            }
+            
+            results.sort_by(|a, b| a.name.cmp(&b.name));
+            for result in results {
+                if !result.summary.is_empty() {
+                    debug!("Summary for {}: {:?}", result.name, result.summary);
+                }
+                if self.options.dfs_stats {
+                    crate::report::report_dfs_stats(&result.name, &result.stats);
+                }
+                crate::report::report_function_start(&result.name, &result.body);
+                crate::report::report_function_findings(&result.findings, &result.body);
+                crate::report::report_function_end(&result.name, result.stats.outcome());
+                if let Some(reason) = result.stats.incomplete_reason(&dfs_config) {
+                    incomplete.push(crate::report::IncompleteCoverage {
+                        function: result.name.clone(),
+                        outcome: result.stats.outcome(),
+                        reason,
+                    });
+                }
+                findings.extend(result.findings);
+                analyzed_count += 1;
+            }
+        }
+        
         debug!("Analysis complete: analyzed {} functions", analyzed_count);
+        
+        crate::report::emit_findings(tcx, &crate_name, &findings, &incomplete);
+        crate::report::report_incomplete_coverage(&incomplete, analyzed_count);
+        if let Some(level) = self.options.diagnostics {
+            crate::report::emit_diagnostics(tcx, level, &findings);
+        }
+    }
+    
+    /// Dump the call graph when `TAINT_ANA_CALLGRAPH` is set to `dot` or `json`.
+    /// The file is written to the output directory as `<crate>.callgraph.<ext>`.
+    fn dump_callgraph<'tcx>(
+        &self,
+        callgraph: &crate::callgraph::CallGraph<'tcx>,
+        tcx: TyCtxt<'tcx>,
+        crate_name: &str,
+    ) {
+        let format = match std::env::var("TAINT_ANA_CALLGRAPH") {
+            Ok(format) => format,
+            Err(_) => return,
+        };
+        let content = match format.as_str() {
+            "dot" => callgraph.to_dot(tcx),
+            "json" => callgraph.to_json(tcx).to_string(),
+            other => {
+                eprintln!("⚠️  Warning: unknown TAINT_ANA_CALLGRAPH format {:?}, expected dot or json", other);
+                return;
+            }
+        };
+        let path = self.output_directory.join(format!("{}.callgraph.{}", crate_name, format));
+        match std::fs::write(&path, content) {
+            Ok(()) => debug!("Call graph written to {}", path.display()),
+            Err(e) => eprintln!("⚠️  Warning: failed to write call graph to {}: {}", path.display(), e),
+        }
     }
 }
 
@@ -181,49 +321,88 @@ fn get_mir_body_all<'tcx>(
     Some(tcx.instance_mir(instance.def))
 }
 
-/// Get DFS configuration - configured directly in code
-fn get_dfs_config() -> crate::dfs::DfsConfig {
-    // 直接在这里配置参数
-    crate::dfs::DfsConfig {
-        k_predecessor: 2,        // 设置 k 值：0=不敏感, 1-3=推荐, >3=高精度
-        max_visits_per_block: 10, // 单个 block 最大访问次数
+/// Get the MIR body of an instance, with the instance's generic arguments substituted.
+/// Polymorphic (identity-substituted) and non-generic instances use the body as is.
+fn instance_body<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> Cow<'tcx, Body<'tcx>> {
+    let body = tcx.instance_mir(instance.def);
+    if instance.args.is_empty() || instance.args.has_param() {
+        return Cow::Borrowed(body);
     }
-    
-    // 如果需要不同的配置，直接修改上面的数值即可
+    Cow::Owned(instance.instantiate_mir_and_normalize_erasing_regions(
+        tcx,
+        TypingEnv::fully_monomorphized(),
+        ty::EarlyBinder::bind(body.clone()),
+    ))
+}
+
+/// Name used in reports. Generic functions include the instantiation that was analyzed,
+/// e.g. `Owned::<std::vec::Vec<i32>>::as_raw` or `Owned::<T>::as_raw` for the polymorphic body.
+fn function_name<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> String {
+    let def_id = instance.def_id();
+    if tcx.generics_of(def_id).requires_monomorphization(tcx) && !tcx.is_closure_like(def_id) {
+        tcx.def_path_str_with_args(def_id, instance.args)
+    } else {
+        tcx.def_path_str(def_id)
+    }
+}
+
+/// Get DFS configuration from the `[dfs]` section of taint-ana.toml
+fn get_dfs_config() -> crate::dfs::DfsConfig {
     // 例如：
-    // - 快速分析: k_predecessor: 0, max_visits_per_block: 10
-    // - 平衡模式: k_predecessor: 2, max_visits_per_block: 10
-    // - 高精度: k_predecessor: 3, max_visits_per_block: 20
+    // - 快速分析: k_predecessor = 0, max_visits_per_block = 10
+    // - 平衡模式: k_predecessor = 2, max_visits_per_block = 10（默认）
+    // - 高精度: k_predecessor = 3, max_visits_per_block = 20
+    crate::config::get().dfs.clone()
+}
+
+/// Everything produced by the analysis of one function.
+/// Reports are printed by the caller once the whole level is analyzed, so that the
+/// output order does not depend on which thread finished first.
+struct FunctionAnalysis<'tcx> {
+    instance: Instance<'tcx>,
+    name: String,
+    body: Cow<'tcx, Body<'tcx>>,
+    /// Drop/escape summary observed at the function's `Return`s
+    summary: crate::summary::FnSummary,
+    /// Findings merged across paths
+    findings: Vec<crate::finding::Finding>,
+    /// Traversal statistics, including whether a budget stopped the traversal
+    stats: crate::dfs::DfsStats,
 }
 
-/// Analyze a function using DFS traversal with state management
+/// Analyze a function using DFS traversal with state management.
+/// Only reads `tcx` and `summaries`, so functions can be analyzed on several threads.
 fn analyze_function<'tcx>(
     tcx: TyCtxt<'tcx>,
     instance: Instance<'tcx>,
-    body: &'tcx Body<'tcx>,
-) {
-    let def_id = instance.def_id();
-    let name = tcx.def_path_str(def_id);
-    
-    // Report function analysis start
-    crate::report::report_function_start(&name, body);
+    summaries: &crate::summary::SummaryMap<'tcx>,
+) -> FunctionAnalysis<'tcx> {
+    let name = function_name(tcx, instance);
+    let body = instance_body(tcx, instance);
     
     // Create a BindingManager for this function
     let mut manager = crate::state::BindingManager::new(&name);
     
     // Register all locals
     for (local_idx, _local_decl) in body.local_decls.iter_enumerated() {
-        let id_str = format!("_{}", local_idx.as_usize());
-        manager.register(id_str, None);
+        manager.register(crate::place::PlaceId::local(local_idx.as_usize()), None);
     }
+    // Known values for path-feasibility pruning
+    crate::feasibility::seed(tcx, &body, &mut manager);
+    
+    // Collects the errors reported on all paths of this function
+    let mut findings = crate::finding::FindingCollector::default();
+    
+    // Collects parameter drop/escape state at every Return
+    let mut summary_builder = crate::summary::SummaryBuilder::for_body(&body);
     
     // Get DFS configuration from environment
     let config = get_dfs_config();
     
-    // Use DFS traversal with state management for branches
+    // Traverse with the configured engine (path-by-path DFS or worklist fixpoint)
     use rustc_middle::mir::START_BLOCK;
-    let stats = crate::dfs::dfs_visit_with_manager_ex(
-        body,
+    let stats = crate::dfs::visit_with_engine(
+        &body,
         START_BLOCK,
         &mut manager,
         config,
@@ -231,39 +410,29 @@ fn analyze_function<'tcx>(
             let bb = &body.basic_blocks[bb_idx];
             
             // Analyze each statement in this basic block
-            for stmt in &bb.statements {
-                crate::detect::detect_stmt(stmt, mgr, bb_idx, &name, body);
+            for (stmt_idx, stmt) in bb.statements.iter().enumerate() {
+                crate::detect::detect_stmt(stmt, mgr, &mut findings, bb_idx, stmt_idx, &name, &body, tcx);
+                crate::feasibility::track_statement(tcx, &body, stmt, mgr);
             }
             
             // Analyze terminator
             if let Some(ref terminator) = bb.terminator {
-                crate::detect::detect_terminator(terminator, mgr, body, tcx, bb_idx, &name);
+                crate::detect::detect_terminator(terminator, mgr, &mut findings, &body, tcx, bb_idx, &name, summaries);
+                crate::feasibility::track_terminator(tcx, &body, terminator, mgr);
+                if let rustc_middle::mir::TerminatorKind::Return = terminator.kind {
+                    summary_builder.record_return(mgr);
+                }
             }
         },
     );
     
-    // Print DFS statistics (可选：注释掉这行以禁用统计输出)
-    // print_dfs_stats(&name, &stats);
-    
-    // Report function analysis end
-    crate::report::report_function_end(&name);
-}
-
-/// Print DFS statistics
-fn print_dfs_stats(func_name: &str, stats: &crate::dfs::DfsStats) {
-    println!("\n=== DFS Statistics for {} ===", func_name);
-    println!("  Total visit attempts: {}", stats.total_visit_attempts);
-    println!("  Successful visits: {}", stats.successful_visits);
-    println!("  Skipped (duplicate path): {}", stats.skipped_duplicate_path);
-    println!("  Skipped (max visits): {}", stats.skipped_max_visits);
-    println!("  Unique paths explored: {}", stats.unique_paths);
-    println!("  Unique blocks visited: {}", stats.unique_blocks);
-    
-    // 计算路径爆炸因子
-    if stats.unique_blocks > 0 {
-        let explosion_factor = stats.unique_paths as f64 / stats.unique_blocks as f64;
-        println!("  Path explosion factor: {:.2}x", explosion_factor);
+    FunctionAnalysis {
+        instance,
+        name,
+        body,
+        summary: summary_builder.finish(),
+        findings: findings.into_findings(),
+        stats,
     }
-    println!("================================\n");
 }
 
@@ -0,0 +1,380 @@
+//! Call graph over the local crate, modelled on lockbud's `CallGraph`.
+//! Nodes are monomorphized `Instance`s; edges carry every call site between
+//! a caller and a callee. Exposes bottom-up SCC levels and DOT/JSON dumps.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{Graph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::{Directed, Direction};
+use rustc_middle::mir::{Body, Local, LocalDecl, Location, Terminator, TerminatorKind};
+use rustc_middle::mir::visit::Visitor;
+use rustc_middle::ty::{self, Instance, InstanceKind, TyCtxt, TyKind, TypeVisitableExt, TypingEnv};
+
+/// 调用图中节点的下标
+pub type InstanceId = NodeIndex;
+
+/// 调用图节点
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CallGraphNode<'tcx> {
+    /// 本地 crate 中有 MIR body 的实例
+    WithBody(Instance<'tcx>),
+    /// 外部函数、intrinsic、shim 等（不展开）
+    WithoutBody(Instance<'tcx>),
+}
+
+impl<'tcx> CallGraphNode<'tcx> {
+    pub fn instance(&self) -> &Instance<'tcx> {
+        match self {
+            CallGraphNode::WithBody(instance) | CallGraphNode::WithoutBody(instance) => instance,
+        }
+    }
+
+    pub fn has_body(&self) -> bool {
+        matches!(self, CallGraphNode::WithBody(_))
+    }
+}
+
+/// 调用点位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallSiteLocation {
+    /// 直接调用（Call terminator 的位置）
+    Direct(Location),
+    /// 闭包定义（持有闭包的 local）
+    ClosureDef(Local),
+}
+
+/// 本地 crate 的调用图
+pub struct CallGraph<'tcx> {
+    pub graph: Graph<CallGraphNode<'tcx>, Vec<CallSiteLocation>, Directed>,
+    index: HashMap<Instance<'tcx>, InstanceId>,
+}
+
+impl<'tcx> CallGraph<'tcx> {
+    pub fn new() -> Self {
+        Self {
+            graph: Graph::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// 以给定实例为节点构建调用图，并遍历它们的 MIR 收集调用点
+    pub fn analyze(
+        &mut self,
+        instances: Vec<Instance<'tcx>>,
+        tcx: TyCtxt<'tcx>,
+        typing_env: TypingEnv<'tcx>,
+    ) {
+        // 1. 添加所有有 body 的节点
+        let idx_insts: Vec<_> = instances
+            .into_iter()
+            .map(|instance| (self.add_node(CallGraphNode::WithBody(instance)), instance))
+            .collect();
+
+        // 2. 遍历每个函数的 MIR，收集调用点
+        for (caller_idx, caller) in idx_insts {
+            let body = tcx.instance_mir(caller.def);
+            // 多态实例（泛型参数未实例化）需要在其自身的 where 子句下解析调用
+            let typing_env = if caller.args.has_param() {
+                TypingEnv::post_analysis(tcx, caller.def_id())
+            } else {
+                typing_env
+            };
+            let mut collector = CallSiteCollector::new(caller, body, tcx, typing_env);
+            collector.visit_body(body);
+
+            // 3. 为每个调用点添加边（同一对 caller/callee 的调用点合并到一条边上）
+            for (callee, location) in collector.finish() {
+                let callee_idx = match self.index.get(&callee) {
+                    Some(&idx) => idx,
+                    None => self.add_node(CallGraphNode::WithoutBody(callee)),
+                };
+                match self.graph.find_edge(caller_idx, callee_idx) {
+                    Some(edge) => self.graph[edge].push(location),
+                    None => {
+                        self.graph.add_edge(caller_idx, callee_idx, vec![location]);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_node(&mut self, node: CallGraphNode<'tcx>) -> InstanceId {
+        let instance = *node.instance();
+        if let Some(&idx) = self.index.get(&instance) {
+            return idx;
+        }
+        let idx = self.graph.add_node(node);
+        self.index.insert(instance, idx);
+        idx
+    }
+
+    /// 强连通分量，按自底向上排列（被调者所在的 SCC 在调用者之前）
+    pub fn sccs(&self) -> Vec<Vec<InstanceId>> {
+        bottom_up_sccs(&self.graph)
+    }
+
+    /// 按层划分的自底向上顺序，只包含有 body 的节点
+    ///
+    /// 每个 SCC 只调用更低层的 SCC，同一层的 SCC 之间没有调用关系，可以并行分析；
+    /// SCC 内部保持 `sccs` 中的顺序
+    pub fn bottom_up_levels(&self) -> Vec<Vec<Vec<InstanceId>>> {
+        scc_levels(&self.graph, self.sccs())
+            .into_iter()
+            .map(|level| {
+                level
+                    .into_iter()
+                    .map(|scc| scc.into_iter().filter(|&idx| self.graph[idx].has_body()).collect::<Vec<_>>())
+                    .filter(|scc| !scc.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|level| !level.is_empty())
+            .collect()
+    }
+
+    /// `sccs`/`bottom_up_levels` 中的一个 SCC 是否是递归调用环（多个成员，或者自递归）
+    pub fn is_recursive(&self, scc: &[InstanceId]) -> bool {
+        is_recursive_scc(&self.graph, scc)
+    }
+
+    /// 导出为 Graphviz DOT 格式
+    pub fn to_dot(&self, tcx: TyCtxt<'tcx>) -> String {
+        let mut dot = String::from("digraph callgraph {\n");
+        for idx in self.graph.node_indices() {
+            let node = &self.graph[idx];
+            let shape = if node.has_body() { "box" } else { "ellipse" };
+            let label = escape_dot(&tcx.def_path_str_with_args(node.instance().def_id(), node.instance().args));
+            let _ = writeln!(dot, "    {} [label=\"{}\", shape={}];", idx.index(), label, shape);
+        }
+        for edge in self.graph.edge_references() {
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [label=\"{}\"];",
+                edge.source().index(),
+                edge.target().index(),
+                edge.weight().len()
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// 导出为 JSON（nodes + edges，边上带所有调用点）
+    pub fn to_json(&self, tcx: TyCtxt<'tcx>) -> serde_json::Value {
+        let recursive: HashSet<InstanceId> =
+            self.sccs().into_iter().filter(|scc| self.is_recursive(scc)).flatten().collect();
+        let nodes: Vec<_> = self
+            .graph
+            .node_indices()
+            .map(|idx| {
+                let node = &self.graph[idx];
+                serde_json::json!({
+                    "id": idx.index(),
+                    "name": tcx.def_path_str_with_args(node.instance().def_id(), node.instance().args),
+                    "has_body": node.has_body(),
+                    "recursive": recursive.contains(&idx),
+                })
+            })
+            .collect();
+        let edges: Vec<_> = self
+            .graph
+            .edge_references()
+            .map(|edge| {
+                let callsites: Vec<_> = edge
+                    .weight()
+                    .iter()
+                    .map(|site| match site {
+                        CallSiteLocation::Direct(location) => serde_json::json!({
+                            "kind": "direct",
+                            "block": location.block.index(),
+                            "statement": location.statement_index,
+                        }),
+                        CallSiteLocation::ClosureDef(local) => serde_json::json!({
+                            "kind": "closure_def",
+                            "local": format!("{:?}", local),
+                        }),
+                    })
+                    .collect();
+                serde_json::json!({
+                    "caller": edge.source().index(),
+                    "callee": edge.target().index(),
+                    "callsites": callsites,
+                })
+            })
+            .collect();
+        serde_json::json!({ "nodes": nodes, "edges": edges })
+    }
+}
+
+/// 按自底向上顺序返回 SCC
+///
+/// petgraph 的 `tarjan_scc` 已经按逆拓扑序返回（边 a -> b 时 b 的 SCC 在前）
+fn bottom_up_sccs<N, E>(graph: &Graph<N, E, Directed>) -> Vec<Vec<NodeIndex>> {
+    tarjan_scc(graph)
+}
+
+/// 把自底向上排列的 SCC 分层：SCC 的层数为其被调 SCC 的最大层数加一（不调用其他 SCC 时为 0）
+fn scc_levels<N, E>(graph: &Graph<N, E, Directed>, sccs: Vec<Vec<NodeIndex>>) -> Vec<Vec<Vec<NodeIndex>>> {
+    let mut level_of: HashMap<NodeIndex, usize> = HashMap::new();
+    let mut levels: Vec<Vec<Vec<NodeIndex>>> = Vec::new();
+    for scc in sccs {
+        // 被调 SCC 已经分好层；同一 SCC 内的边此时还不在 level_of 中
+        let level = scc
+            .iter()
+            .flat_map(|&idx| graph.neighbors_directed(idx, Direction::Outgoing))
+            .filter_map(|succ| level_of.get(&succ).map(|level| level + 1))
+            .max()
+            .unwrap_or(0);
+        for &idx in &scc {
+            level_of.insert(idx, level);
+        }
+        if levels.len() <= level {
+            levels.resize_with(level + 1, Vec::new);
+        }
+        levels[level].push(scc);
+    }
+    levels
+}
+
+/// SCC 处于递归调用环中：有多个成员，或者唯一的成员调用自己
+fn is_recursive_scc<N, E>(graph: &Graph<N, E, Directed>, scc: &[NodeIndex]) -> bool {
+    match scc {
+        [idx] => graph.find_edge(*idx, *idx).is_some(),
+        _ => scc.len() > 1,
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// 遍历单个函数体，收集其中的调用点
+struct CallSiteCollector<'a, 'tcx> {
+    caller: Instance<'tcx>,
+    body: &'a Body<'tcx>,
+    tcx: TyCtxt<'tcx>,
+    typing_env: TypingEnv<'tcx>,
+    callsites: Vec<(Instance<'tcx>, CallSiteLocation)>,
+}
+
+impl<'a, 'tcx> CallSiteCollector<'a, 'tcx> {
+    fn new(
+        caller: Instance<'tcx>,
+        body: &'a Body<'tcx>,
+        tcx: TyCtxt<'tcx>,
+        typing_env: TypingEnv<'tcx>,
+    ) -> Self {
+        Self {
+            caller,
+            body,
+            tcx,
+            typing_env,
+            callsites: Vec::new(),
+        }
+    }
+
+    fn finish(self) -> Vec<(Instance<'tcx>, CallSiteLocation)> {
+        self.callsites
+    }
+
+    /// 用调用者的泛型参数实例化 MIR 中的类型
+    fn monomorphize(&self, ty: ty::Ty<'tcx>) -> ty::Ty<'tcx> {
+        self.caller.instantiate_mir_and_normalize_erasing_regions(
+            self.tcx,
+            self.typing_env,
+            ty::EarlyBinder::bind(ty),
+        )
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for CallSiteCollector<'a, 'tcx> {
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        if let TerminatorKind::Call { ref func, .. } = terminator.kind {
+            let func_ty = self.monomorphize(func.ty(self.body, self.tcx));
+            if let TyKind::FnDef(def_id, args) = *func_ty.kind() {
+                if let Ok(Some(callee)) = Instance::try_resolve(self.tcx, self.typing_env, def_id, args) {
+                    self.callsites.push((callee, CallSiteLocation::Direct(location)));
+                }
+            }
+        }
+        self.super_terminator(terminator, location);
+    }
+
+    fn visit_local_decl(&mut self, local: Local, local_decl: &LocalDecl<'tcx>) {
+        let func_ty = self.monomorphize(local_decl.ty);
+        if let TyKind::Closure(def_id, args) = *func_ty.kind() {
+            if let Ok(Some(callee)) = Instance::try_resolve(self.tcx, self.typing_env, def_id, args) {
+                if matches!(callee.def, InstanceKind::Item(_)) {
+                    self.callsites.push((callee, CallSiteLocation::ClosureDef(local)));
+                }
+            }
+        }
+        self.super_local_decl(local, local_decl);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造调用图: 0 -> 1 -> 2, 1 -> 3 -> 1（1 和 3 互相递归）
+    fn sample_graph() -> (Graph<u32, (), Directed>, Vec<NodeIndex>) {
+        let mut graph = Graph::new();
+        let nodes: Vec<_> = (0..4).map(|i| graph.add_node(i)).collect();
+        graph.add_edge(nodes[0], nodes[1], ());
+        graph.add_edge(nodes[1], nodes[2], ());
+        graph.add_edge(nodes[1], nodes[3], ());
+        graph.add_edge(nodes[3], nodes[1], ());
+        (graph, nodes)
+    }
+
+    /// 测试1: 被调者所在的 SCC 在调用者之前
+    #[test]
+    fn test_bottom_up_sccs_order() {
+        let (graph, nodes) = sample_graph();
+        let sccs = bottom_up_sccs(&graph);
+        let position = |n: NodeIndex| sccs.iter().position(|scc| scc.contains(&n)).unwrap();
+
+        assert_eq!(sccs.len(), 3);
+        assert!(position(nodes[2]) < position(nodes[1]));
+        assert!(position(nodes[1]) < position(nodes[0]));
+        // 1 和 3 在同一个 SCC
+        assert_eq!(position(nodes[1]), position(nodes[3]));
+    }
+
+    /// 测试2: 分层后同一层的 SCC 互不调用，被调者总在更低层
+    #[test]
+    fn test_scc_levels() {
+        let (mut graph, nodes) = sample_graph();
+        // 4 -> 2：与 SCC {1, 3} 同层
+        let extra = graph.add_node(4);
+        graph.add_edge(extra, nodes[2], ());
+        let levels = scc_levels(&graph, bottom_up_sccs(&graph));
+        let level_of = |n: NodeIndex| levels.iter().position(|level| level.iter().any(|scc| scc.contains(&n))).unwrap();
+
+        assert_eq!(levels.len(), 3);
+        assert_eq!(level_of(nodes[2]), 0);
+        assert_eq!(level_of(nodes[1]), 1);
+        assert_eq!(level_of(nodes[3]), 1);
+        assert_eq!(level_of(extra), 1);
+        assert_eq!(level_of(nodes[0]), 2);
+        assert_eq!(levels[1].len(), 2);
+    }
+
+    /// 测试3: 递归检测（互相递归和自递归）
+    #[test]
+    fn test_is_recursive_scc() {
+        let (mut graph, nodes) = sample_graph();
+        let scc_of = |graph: &Graph<u32, (), Directed>, n: NodeIndex| {
+            bottom_up_sccs(graph).into_iter().find(|scc| scc.contains(&n)).unwrap()
+        };
+        assert!(!is_recursive_scc(&graph, &scc_of(&graph, nodes[0])));
+        assert!(is_recursive_scc(&graph, &scc_of(&graph, nodes[1])));
+        assert!(!is_recursive_scc(&graph, &scc_of(&graph, nodes[2])));
+
+        graph.add_edge(nodes[2], nodes[2], ());
+        assert!(is_recursive_scc(&graph, &scc_of(&graph, nodes[2])));
+    }
+}
@@ -0,0 +1,386 @@
+//! `taint-ana.toml` configuration.
+//! The file is looked up in the analyzed crate's root (`CARGO_MANIFEST_DIR`, falling back to the
+//! current directory) or taken from `--config <path>` in `TAINT_ANA_FLAGS`. It is loaded and
+//! validated once, before compilation starts, command-line options are applied on top of it, and
+//! the result is read through `config::get()` afterwards.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+
+use crate::dfs::DfsConfig;
+use crate::options::{Detector, Options};
+
+/// 配置文件名
+pub const CONFIG_FILE_NAME: &str = "taint-ana.toml";
+
+/// `[patterns]`：按函数名匹配的特殊函数
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PatternsConfig {
+    /// 返回值与第一个参数别名的函数（函数名子串，如 `as_ptr`），调用后绑定返回值和参数
+    pub alias_returning: Vec<String>,
+    /// 会 drop 第一个参数的自定义函数（完整路径子串，如 `my_crate::free`）；标准库的
+    /// `mem::drop`、`ptr::drop_in_place`、`ManuallyDrop::drop` 按 DefId 识别，不需要配置
+    pub drop_functions: Vec<String>,
+}
+
+impl Default for PatternsConfig {
+    fn default() -> Self {
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+        Self {
+            alias_returning: strings(&[
+                // 原始指针操作
+                "as_mut_ptr", "as_ptr",
+                // 引用转换
+                "as_ref", "as_mut",
+                // 原始指针构造
+                "from_raw_parts", "into_raw", "from_raw", "_as_raw",
+            ]),
+            drop_functions: Vec::new(),
+        }
+    }
+}
+
+/// `[paths]`：按源文件路径（相对 crate 根目录）过滤要分析的函数
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PathsConfig {
+    /// 只分析匹配的文件；为空表示全部
+    pub include: Vec<String>,
+    /// 跳过匹配的文件（优先于 include）
+    pub exclude: Vec<String>,
+}
+
+/// `[detectors]`：启用的检测器
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DetectorsConfig {
+    pub enabled: Vec<Detector>,
+}
+
+impl Default for DetectorsConfig {
+    fn default() -> Self {
+        Self { enabled: Detector::DEFAULT.to_vec() }
+    }
+}
+
+/// `[taint]`：在内置规则之外追加的 source/sink/sanitizer（格式同 `TAINT_ANA_SOURCES` 等环境变量）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TaintConfig {
+    pub sources: Vec<String>,
+    pub sinks: Vec<String>,
+    pub sanitizers: Vec<String>,
+}
+
+/// 编译后的 include/exclude glob
+#[derive(Debug, Clone)]
+struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+/// `taint-ana.toml` 的内容
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub dfs: DfsConfig,
+    pub patterns: PatternsConfig,
+    pub paths: PathsConfig,
+    pub detectors: DetectorsConfig,
+    pub taint: TaintConfig,
+    /// 配置所在的 crate 根目录，用于把源文件路径转换为相对路径
+    #[serde(skip)]
+    root: Option<PathBuf>,
+    #[serde(skip)]
+    path_filter: Option<PathFilter>,
+}
+
+impl Config {
+    /// 解析并校验配置内容
+    pub fn from_toml_str(content: &str) -> Result<Self, String> {
+        let mut config: Config = toml::from_str(content).map_err(|e| e.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 读取并校验配置文件
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        let mut config = Self::from_toml_str(&content)
+            .map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+        config.root = path.parent().map(Path::to_path_buf);
+        Ok(config)
+    }
+
+    /// 查找并加载配置：`--config <path>` 优先，否则使用 crate 根目录下的
+    /// `taint-ana.toml`；都不存在时使用默认配置
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        if let Some(path) = path {
+            return Self::from_file(path);
+        }
+        let root = std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .or_else(|| std::env::current_dir().ok());
+        match root.map(|root| root.join(CONFIG_FILE_NAME)) {
+            Some(path) if path.is_file() => Self::from_file(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// 用命令行选项覆盖配置文件中的值
+    pub fn apply_options(&mut self, options: &Options) {
+        if let Some(k) = options.k_predecessor {
+            self.dfs.k_predecessor = k;
+        }
+        if let Some(max_visits) = options.max_visits_per_block {
+            self.dfs.max_visits_per_block = max_visits;
+        }
+        if let Some(engine) = options.engine {
+            self.dfs.engine = engine;
+        }
+        if let Some(unwind) = options.unwind {
+            self.dfs.unwind = unwind;
+        }
+        if let Some(ms) = options.time_budget_ms {
+            self.dfs.time_budget_ms = Some(ms);
+        }
+        if let Some(steps) = options.step_budget {
+            self.dfs.step_budget = Some(steps);
+        }
+        if let Some(detectors) = &options.detectors {
+            self.detectors.enabled = detectors.clone();
+        }
+    }
+
+    /// 语义校验（字段名和类型已由 serde 校验）
+    fn validate(&mut self) -> Result<(), String> {
+        if self.dfs.max_visits_per_block == 0 {
+            return Err("dfs.max_visits_per_block must be at least 1".to_string());
+        }
+        if self.dfs.time_budget_ms == Some(0) {
+            return Err("dfs.time_budget_ms must be at least 1".to_string());
+        }
+        if self.dfs.step_budget == Some(0) {
+            return Err("dfs.step_budget must be at least 1".to_string());
+        }
+        for (key, patterns) in [
+            ("patterns.alias_returning", &self.patterns.alias_returning),
+            ("patterns.drop_functions", &self.patterns.drop_functions),
+        ] {
+            if patterns.iter().any(|p| p.trim().is_empty()) {
+                return Err(format!("{} must not contain empty patterns", key));
+            }
+        }
+        for (key, specs) in [
+            ("taint.sources", &self.taint.sources),
+            ("taint.sinks", &self.taint.sinks),
+            ("taint.sanitizers", &self.taint.sanitizers),
+        ] {
+            for spec in specs {
+                crate::taint::parse_rule_spec(spec).map_err(|e| format!("{}: {}", key, e))?;
+            }
+        }
+
+        let include = if self.paths.include.is_empty() {
+            None
+        } else {
+            Some(build_globset("paths.include", &self.paths.include)?)
+        };
+        let exclude = build_globset("paths.exclude", &self.paths.exclude)?;
+        self.path_filter = Some(PathFilter { include, exclude });
+        Ok(())
+    }
+
+    /// 源文件是否需要分析（路径相对 crate 根目录匹配 include/exclude）
+    pub fn is_path_included(&self, file_path: &str) -> bool {
+        let current_dir = std::env::current_dir().ok();
+        self.is_path_included_from(Path::new(file_path), current_dir.as_deref())
+    }
+
+    /// cargo 传给 rustc 的相对路径相对当前目录：workspace 成员是 workspace 根目录下的
+    /// `member/src/lib.rs`，先拼成绝对路径再去掉 crate 根目录
+    fn is_path_included_from(&self, path: &Path, current_dir: Option<&Path>) -> bool {
+        let Some(filter) = &self.path_filter else { return true };
+        let absolute = match current_dir {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path.to_path_buf(),
+        };
+        let relative = self
+            .root
+            .as_deref()
+            .and_then(|root| absolute.strip_prefix(root).ok())
+            .unwrap_or(path);
+        if filter.exclude.is_match(relative) {
+            return false;
+        }
+        filter.include.as_ref().is_none_or(|include| include.is_match(relative))
+    }
+
+    pub fn is_enabled(&self, detector: Detector) -> bool {
+        self.detectors.enabled.contains(&detector)
+    }
+}
+
+fn build_globset(key: &str, patterns: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| format!("{}: invalid glob {:?}: {}", key, pattern, e))?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| format!("{}: {}", key, e))
+}
+
+/// 全局配置（在 main 中加载一次）
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// 设置全局配置；只能在分析开始前调用一次
+pub fn init(config: Config) {
+    if CONFIG.set(config).is_err() {
+        eprintln!("⚠️  Warning: configuration already initialized, ignoring");
+    }
+}
+
+/// 获取全局配置（未初始化时使用默认配置）
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::{TraversalEngine, UnwindPaths};
+
+    /// 测试1: 空配置使用默认值
+    #[test]
+    fn test_empty_config_uses_defaults() {
+        let config = Config::from_toml_str("").unwrap();
+        assert_eq!(config.dfs.k_predecessor, 2);
+        assert_eq!(config.dfs.max_visits_per_block, 10);
+        assert_eq!(config.dfs.engine, TraversalEngine::Dfs);
+        assert_eq!(config.dfs.unwind, UnwindPaths::Include);
+        assert_eq!(config.dfs.time_budget_ms, None);
+        assert!(config.patterns.alias_returning.contains(&"as_ptr".to_string()));
+        assert!(config.patterns.drop_functions.is_empty());
+        assert!(config.is_enabled(Detector::UseAfterDrop));
+        assert!(config.is_enabled(Detector::Taint));
+        assert!(!config.is_enabled(Detector::Leak));
+        assert!(config.is_path_included("src/main.rs"));
+    }
+
+    /// 测试2: 完整配置
+    #[test]
+    fn test_full_config() {
+        let config = Config::from_toml_str(
+            r#"
+            [dfs]
+            k_predecessor = 3
+            max_visits_per_block = 20
+            engine = "worklist"
+            unwind = "skip"
+            time_budget_ms = 500
+            step_budget = 100000
+
+            [patterns]
+            alias_returning = ["as_ptr", "get_raw"]
+            drop_functions = ["std::mem::drop", "my_crate::free"]
+
+            [paths]
+            include = ["src/**"]
+            exclude = ["src/toys/**"]
+
+            [detectors]
+            enabled = ["taint"]
+
+            [taint]
+            sources = ["my_crate::read_input"]
+            sinks = ["my_crate::run_query#1"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.dfs.k_predecessor, 3);
+        assert_eq!(config.dfs.max_visits_per_block, 20);
+        assert_eq!(config.dfs.engine, TraversalEngine::Worklist);
+        assert_eq!(config.dfs.unwind, UnwindPaths::Skip);
+        assert_eq!(config.dfs.time_budget_ms, Some(500));
+        assert_eq!(config.dfs.step_budget, Some(100000));
+        assert_eq!(config.patterns.alias_returning, vec!["as_ptr", "get_raw"]);
+        assert!(!config.is_enabled(Detector::UseAfterDrop));
+        assert!(config.is_enabled(Detector::Taint));
+        assert!(config.is_path_included("src/lib.rs"));
+        assert!(!config.is_path_included("src/toys/example.rs"));
+        assert!(!config.is_path_included("benches/bench.rs"));
+    }
+
+    /// 测试3: 未知的键给出明确错误
+    #[test]
+    fn test_unknown_key_is_rejected() {
+        let err = Config::from_toml_str("[dfs]\nk = 3\n").unwrap_err();
+        assert!(err.contains("unknown field `k`"), "{}", err);
+        assert!(err.contains("k_predecessor"), "{}", err);
+
+        let err = Config::from_toml_str("[dfss]\n").unwrap_err();
+        assert!(err.contains("unknown field `dfss`"), "{}", err);
+    }
+
+    /// 测试4: 非法的值给出明确错误
+    #[test]
+    fn test_invalid_values_are_rejected() {
+        let err = Config::from_toml_str("[detectors]\nenabled = [\"double-free\"]\n").unwrap_err();
+        assert!(err.contains("unknown variant `double-free`"), "{}", err);
+
+        let err = Config::from_toml_str("[dfs]\nmax_visits_per_block = 0\n").unwrap_err();
+        assert!(err.contains("max_visits_per_block"), "{}", err);
+
+        let err = Config::from_toml_str("[dfs]\nstep_budget = 0\n").unwrap_err();
+        assert!(err.contains("step_budget"), "{}", err);
+
+        let err = Config::from_toml_str("[paths]\nexclude = [\"src/[\"]\n").unwrap_err();
+        assert!(err.contains("paths.exclude"), "{}", err);
+
+        let err = Config::from_toml_str("[taint]\nsinks = [\"my::sink#x\"]\n").unwrap_err();
+        assert!(err.contains("taint.sinks"), "{}", err);
+    }
+
+    /// 测试5: 命令行选项覆盖配置文件
+    #[test]
+    fn test_options_override_config() {
+        let mut config = Config::from_toml_str("[dfs]\nk_predecessor = 3\nmax_visits_per_block = 20\n").unwrap();
+        let options = Options {
+            k_predecessor: Some(1),
+            detectors: Some(vec![Detector::Taint]),
+            ..Default::default()
+        };
+        config.apply_options(&options);
+        assert_eq!(config.dfs.k_predecessor, 1);
+        assert_eq!(config.dfs.max_visits_per_block, 20);
+        assert!(!config.is_enabled(Detector::UseAfterDrop));
+    }
+
+    /// 测试6: 路径相对配置所在目录匹配
+    #[test]
+    fn test_paths_relative_to_root() {
+        let mut config = Config::from_toml_str("[paths]\nexclude = [\"tests/**\"]\n").unwrap();
+        config.root = Some(PathBuf::from("/work/my_crate"));
+        assert!(!config.is_path_included("/work/my_crate/tests/it.rs"));
+        assert!(config.is_path_included("/work/my_crate/src/lib.rs"));
+    }
+
+    /// 测试7: workspace 成员的相对路径相对 workspace 根目录（当前目录）解析
+    #[test]
+    fn test_paths_relative_to_workspace() {
+        let mut config = Config::from_toml_str("[paths]\ninclude = [\"src/**\"]\nexclude = [\"src/gen/**\"]\n").unwrap();
+        config.root = Some(PathBuf::from("/ws/member"));
+        let workspace = Some(Path::new("/ws"));
+        assert!(config.is_path_included_from(Path::new("member/src/lib.rs"), workspace));
+        assert!(!config.is_path_included_from(Path::new("member/src/gen/out.rs"), workspace));
+        assert!(!config.is_path_included_from(Path::new("other/src/lib.rs"), workspace));
+        // 单个 crate：当前目录就是 crate 根目录
+        assert!(config.is_path_included_from(Path::new("src/lib.rs"), Some(Path::new("/ws/member"))));
+    }
+}
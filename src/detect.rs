@@ -1,96 +1,71 @@
 use std::collections::HashSet;
 use std::sync::OnceLock;
 
-use rustc_middle::mir::{Body, Operand, Place, ProjectionElem, Rvalue, Statement, StatementKind, Terminator, TerminatorKind, BasicBlock, PlaceElem};
-use rustc_middle::ty::{TyCtxt, TyKind};
+use rustc_middle::mir::{AggregateKind, Body, CastKind, Const, ConstValue, Operand, RETURN_PLACE, Place, ProjectionElem, Rvalue, Statement, StatementKind, Terminator, TerminatorKind, BasicBlock, PlaceElem};
+use rustc_middle::mir::interpret::Scalar;
+use rustc_middle::ty::{Ty, TyCtxt, TyKind};
+use rustc_middle::ty::adjustment::PointerCoercion;
 use rustc_span::Symbol;
-use rustc_index::Idx;
+use rustc_span::def_id::DefId;
+use rustc_span::source_map::Spanned;
 
-use crate::state::BindingManager;
+use crate::finding::FindingCollector;
+use crate::options::Detector;
+use crate::ownership::DropCall;
+use crate::place::{PathElem, PlaceId};
+use crate::state::{BindingManager, PathEventKind};
+use crate::summary::{FnSummary, SummaryMap};
 
-/// 从 Place 提取基础 local ID（String 格式，如 "_1"）
-fn extract_base_local_from_place(place: &Place) -> Option<String> {
-    let local_usize: usize = place.local.as_usize();
-    Some(format!("_{}", local_usize))
+/// 从 Place 提取基础 local ID（如 `_1`）
+fn extract_base_local_from_place(place: &Place) -> Option<PlaceId> {
+    Some(PlaceId::local(place.local.as_usize()))
 }
 
-/// 从 Place 提取完整的 local ID（String 格式，支持多层嵌套和解引用）
+/// 从 Place 提取完整的 local ID（支持多层嵌套和解引用）
 ///
-/// 示例：
-/// - `_1` → "_1"
-/// - `_1.0` → "_1.0" (结构体字段)
-/// - `_1.3.4.5` → "_1.3.4.5" (嵌套结构体字段)
-/// - `(_1 as Some).0` → "(_1 as 0).0" (枚举字段，variant 0, field 0)
-/// - `((_1.0) as Some).0` → "((_1.0) as 0).0" (结构体字段中的枚举字段)
-/// - `(*_1)` → "*_1" (解引用)
-/// - `(*_1).0` → "*_1.0" (先解引用再访问字段)
-/// - `(*(_1.0))` → "_1.0@deref" (先访问字段再解引用)
-/// - `(**_1)` → "**_1" (两次解引用)
-/// - `(_1.0)[_2]` → "_1.0" (Index 之前的字段)
+/// 示例（括号内为 ID 的显示形式）：
+/// - `_1` → `_1`
+/// - `_1.0` → `_1.0` (结构体字段)
+/// - `_1.3.4.5` → `_1.3.4.5` (嵌套结构体字段)
+/// - `(_1 as Some).0` → `(_1 as 0).0` (枚举字段，variant 0, field 0)
+/// - `((_1.0) as Some).0` → `(_1.0 as 0).0` (结构体字段中的枚举字段)
+/// - `(*_1)` → `*_1` (解引用)
+/// - `(*_1).0` → `*_1.0` (先解引用再访问字段)
+/// - `(*(_1.0))` → `_1.0@deref` (先访问字段再解引用)
+/// - `(**_1)` → `**_1` (两次解引用)
+/// - `(_1.0)[_2]` → `_1.0` (Index 之前的字段)
 ///
 /// 策略：
-/// - Field: 追加 ".{field_index}"
-/// - Downcast + Field: 追加 " as {variant_index}).{field_index}"
-/// - Deref: 前置用 "*" 前缀，后置用 "@deref" 后缀
-/// - Index/ConstantIndex/Subslice: 停止处理（返回当前构建的 ID）
-/// - 其他: 停止处理
-fn extract_local_from_place(place: &Place) -> Option<String> {
-    let base_local = extract_base_local_from_place(place)?;
-
-    let projection = &place.projection;
-
-    if projection.is_empty() {
-        return Some(base_local);
-    }
+/// - Field: `PathElem::Field`
+/// - Downcast + Field: `PathElem::VariantField`
+/// - Deref: `PathElem::Deref`
+/// - Index/ConstantIndex/Subslice、后面没有 Field 的 Downcast: 停止处理（返回当前构建的 ID）
+/// - OpaqueCast/Subtype/UnwrapUnsafeBinder: 不影响字段路径，跳过
+fn extract_local_from_place(place: &Place) -> Option<PlaceId> {
+    let mut projection = Vec::new();
+    let mut elems = place.projection.iter().peekable();
 
-    let mut current_id = base_local.clone();
-    let mut i = 0;
-
-    while i < projection.len() {
-        match &projection[i] {
+    while let Some(elem) = elems.next() {
+        match elem {
             ProjectionElem::Downcast(_, variant_idx) => {
-                // 检查下一个元素是否是 Field
-                if i + 1 < projection.len() {
-                    if let ProjectionElem::Field(field_idx, _) = &projection[i + 1] {
-                        // 找到 Downcast + Field，这是枚举字段访问
-                        let variant_index = variant_idx.as_usize();
-                        let field_index = field_idx.as_usize();
-                        // 追加 " as {variant_index}).{field_index}"
-                        current_id = format!("({} as {}).{}", current_id, variant_index, field_index);
-                        i += 2;  // 跳过 Downcast 和 Field
-                        continue;
-                    }
+                // 检查下一个元素是否是 Field：Downcast + Field 是枚举字段访问
+                if let Some(ProjectionElem::Field(field_idx, _)) = elems.peek() {
+                    projection.push(PathElem::VariantField {
+                        variant: variant_idx.as_u32(),
+                        field: field_idx.as_u32(),
+                    });
+                    elems.next();
+                    continue;
                 }
                 // Downcast 后面没有 Field，停止处理
                 break;
             }
             ProjectionElem::Field(field_idx, _) => {
-                // 检查前面是否有 Downcast（在同一位置）
-                if i > 0 {
-                    if let ProjectionElem::Downcast(_, _) = &projection[i - 1] {
-                        // 前面有 Downcast，这是枚举字段，应该在上一次迭代中处理
-                        i += 1;
-                        continue;
-                    }
-                }
                 // 单独的 Field（没有前面的 Downcast），这是结构体字段
-                let field_index = field_idx.as_usize();
-                // 追加 ".{field_index}"
-                current_id = format!("{}.{}", current_id, field_index);
-                i += 1;
+                projection.push(PathElem::Field(field_idx.as_u32()));
             }
             ProjectionElem::Deref => {
-                // 解引用处理：区分前置和后置
-                // 前置解引用：如果当前 ID 是基础变量或已经有前置解引用（以 * 开头）
-                // 后置解引用：如果当前 ID 已经包含字段访问（包含 . 或 (）
-                if current_id == base_local || current_id.starts_with('*') {
-                    // 前置解引用：添加 * 前缀
-                    current_id = format!("*{}", current_id);
-                } else {
-                    // 后置解引用：添加 @deref 后缀
-                    current_id = format!("{}@deref", current_id);
-                }
-                i += 1;
+                projection.push(PathElem::Deref);
             }
             ProjectionElem::Index(_) |
             ProjectionElem::ConstantIndex { .. } |
@@ -99,24 +74,19 @@ fn extract_local_from_place(place: &Place) -> Option<String> {
                 // Index 之后停止处理
                 break;
             }
-            ProjectionElem::OpaqueCast(_) => {
-                // OpaqueCast 不影响字段路径，继续处理
-                i += 1;
+            ProjectionElem::OpaqueCast(_) |
+            ProjectionElem::Subtype(_) |
+            PlaceElem::UnwrapUnsafeBinder(_) => {
+                // 不影响字段路径，继续处理
             }
-
-            ProjectionElem::Subtype(_)=>{}
-
-            PlaceElem::UnwrapUnsafeBinder(_) => {}
-
-
         }
     }
 
-    Some(current_id)
+    Some(PlaceId::new(place.local.as_usize(), &projection))
 }
 
-/// 提取 Operand 中的 local ID（String 格式）
-fn extract_local_from_operand(operand: &Operand) -> Option<String> {
+/// 提取 Operand 中的 local ID
+fn extract_local_from_operand(operand: &Operand) -> Option<PlaceId> {
     match operand {
         Operand::Copy(place) | Operand::Move(place) => {
             extract_local_from_place(place)
@@ -125,9 +95,9 @@ fn extract_local_from_operand(operand: &Operand) -> Option<String> {
     }
 }
 
-/// 提取 Operand 中的基础 local ID（String 格式）
+/// 提取 Operand 中的基础 local ID
 /// 用于需要基础 local 的场景（如 use_check，需要检查基础 local 是否已 drop）
-fn extract_base_local_from_operand(operand: &Operand) -> Option<String> {
+fn extract_base_local_from_operand(operand: &Operand) -> Option<PlaceId> {
     match operand {
         Operand::Copy(place) | Operand::Move(place) => {
             extract_base_local_from_place(place)
@@ -139,7 +109,17 @@ fn extract_base_local_from_operand(operand: &Operand) -> Option<String> {
 /// 全局黑名单（懒加载，只读取一次）
 static BLACKLIST: OnceLock<HashSet<String>> = OnceLock::new();
 
-pub fn detect_stmt(stmt: &Statement<'_>, manager: &mut BindingManager, bb: BasicBlock, fn_name: &str, body: &Body<'_>) {
+#[allow(clippy::too_many_arguments)]
+pub fn detect_stmt<'tcx>(
+    stmt: &Statement<'tcx>,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+    bb: BasicBlock,
+    stmt_index: usize,
+    fn_name: &str,
+    body: &Body<'tcx>,
+    tcx: TyCtxt<'tcx>,
+) {
     match &stmt.kind {
         StatementKind::Assign(box(left, rValue)) => {
             // 提取完整 ID（包括解引用和字段）用于重新赋值检测
@@ -151,8 +131,8 @@ pub fn detect_stmt(stmt: &Statement<'_>, manager: &mut BindingManager, bb: Basic
             // 检查是否是重新赋值
             // 对于任何形式的 place（包括 *_1.1, _4, (*_4) 等），如果之前被 dropped，重新赋值应该恢复状态
             // 关键：必须在检查右值 use 之前恢复状态，否则 use_check 会误报
-            if let Some(ref target_id) = left_full_id {
-                let was_dropped = manager.is_dropped(target_id);
+            if let Some(target_id) = left_full_id {
+                let was_dropped = manager.is_dropped(target_id) || manager.is_out_of_scope(target_id);
                 if was_dropped {
                     // 这是重新赋值，恢复 drop 状态
                     if is_debug_enabled() {
@@ -163,6 +143,15 @@ pub fn detect_stmt(stmt: &Statement<'_>, manager: &mut BindingManager, bb: Basic
                         );
                     }
                     manager.undrop_group(target_id);
+                    manager.record_event(PathEventKind::Reassign, target_id, None, bb, stmt.source_info.span);
+                }
+            }
+            // 整个 local 被重新赋值：之前的值是否被 move 走、是否拥有过别的内存不再重要
+            if left.as_local().is_some() {
+                if let Some(target) = left_base_id {
+                    manager.set_moved_out(target, false);
+                    manager.set_reclaimed(target, false);
+                    manager.set_freed(target, false);
                 }
             }
             match rValue {
@@ -173,7 +162,14 @@ pub fn detect_stmt(stmt: &Statement<'_>, manager: &mut BindingManager, bb: Basic
                             // 注意：如果这是重新赋值的一部分（左值刚被恢复状态），
                             // 右值的 use_check 应该在重新赋值检测之后，所以这里应该没问题
                             let base_id = extract_base_local_from_place(&place);
-                            use_check_stmt(base_id, manager, stmt, bb, fn_name, body);
+                            use_check_stmt(base_id, manager, findings, stmt, bb, fn_name, body);
+
+                            // 裸指针是 Copy 的：复制出来的指针与原指针指向同一个值
+                            if is_raw_pointer_place(left, body) {
+                                bind_pointer(extract_local_from_place(place), left_base_id, manager, bb, stmt);
+                            }
+                            record_global_store(left, place, manager, bb, stmt);
+                            store_released(left, place, manager);
                         }
                         Operand::Move(place) => {
                             // Move 操作：提取 local ID（支持多层嵌套）
@@ -183,16 +179,32 @@ pub fn detect_stmt(stmt: &Statement<'_>, manager: &mut BindingManager, bb: Basic
                             // 注意：如果这是重新赋值的一部分（左值刚被恢复状态），
                             // 右值的 use_check 应该在重新赋值检测之后，所以这里应该没问题
                             let base_id = extract_base_local_from_place(&place);
-                            use_check_stmt(base_id.clone(), manager, stmt, bb, fn_name, body);
+                            use_check_stmt(base_id, manager, findings, stmt, bb, fn_name, body);
                             
                             // 确保 source_id 已注册
-                            if let Some(ref source) = source_id {
-                                manager.register(source.clone(), None);
+                            if let Some(source) = source_id {
+                                manager.register(source, None);
+                                if place.as_local().is_some() {
+                                    manager.set_moved_out(source, true);
+                                    // from_raw 取得的所有权随值一起 move
+                                    if let (true, Some(target)) = (manager.is_reclaimed(source), left_base_id) {
+                                        manager.register(target, None);
+                                        manager.set_reclaimed(target, true);
+                                    }
+                                    // 交出的所有权（如 `ManuallyDrop`）同样随值 move
+                                    if let (Some(span), Some(target)) = (manager.released(source), left_base_id) {
+                                        manager.clear_released(source);
+                                        if !left.is_indirect_first_projection() {
+                                            manager.register(target, None);
+                                            manager.set_released(target, span);
+                                        }
+                                    }
+                                }
                             }
                             
                             // Move 操作：绑定源变量和目标变量
                             // 使用 left_base_id 作为绑定目标（保持兼容性）
-                            if let (Some(ref source), Some(ref target)) = (source_id, left_base_id) {
+                            if let (Some(source), Some(target)) = (source_id, left_base_id) {
                                 if is_debug_enabled() {
                                     let source_dropped_before = manager.is_dropped(source);
                                     let target_dropped_before = manager.is_dropped(target);
@@ -202,42 +214,60 @@ pub fn detect_stmt(stmt: &Statement<'_>, manager: &mut BindingManager, bb: Basic
                                 
                                 if let Err(e) = manager.bind(source, target) {
                                     eprintln!("⚠️  Warning: bind failed in Move {} -> {}: {}", source, target, e);
-                                } else if is_debug_enabled() {
-                                    let source_dropped_after = manager.is_dropped(source);
-                                    let target_dropped_after = manager.is_dropped(target);
-                                    println!("    [DEBUG] After bind: source_dropped={}, target_dropped={}", 
-                                        source_dropped_after, target_dropped_after);
+                                } else {
+                                    manager.record_event(PathEventKind::Move, target, Some(source), bb, stmt.source_info.span);
+                                    if is_debug_enabled() {
+                                        let source_dropped_after = manager.is_dropped(source);
+                                        let target_dropped_after = manager.is_dropped(target);
+                                        println!("    [DEBUG] After bind: source_dropped={}, target_dropped={}", 
+                                            source_dropped_after, target_dropped_after);
+                                    }
                                 }
                             }
+                            record_global_store(left, place, manager, bb, stmt);
+                            store_released(left, place, manager);
+                        }
+                        Operand::Constant(constant) => {
+                            // 常量地址（如 `static mut` 变量的地址 `const {alloc1: *mut T}`）指向静态内存
+                            if let (Const::Val(ConstValue::Scalar(Scalar::Ptr(..)), _), Some(target)) =
+                                (constant.const_, left_base_id)
+                            {
+                                manager.register(target, None);
+                                manager.mark_global(target);
+                            }
                         }
-                        Operand::Constant(_) => {}
                     }
                 }
                 Rvalue::Repeat(op, _) => {
                     // Repeat: use op (e.g., [x; 3]，重复 use x)
                     // 可能涉及字段访问，使用 extract 更精确
                     let id_opt = extract_local_from_operand(&op);
-                    use_check_stmt(id_opt, manager, stmt, bb, fn_name, body);
+                    use_check_stmt(id_opt, manager, findings, stmt, bb, fn_name, body);
                 }
                 Rvalue::Ref(_, _, place) => {
                     // Ref: use place (借用，读取 source)
-                    // 对于 use_check，需要检查基础 local
-                    let base_id = extract_base_local_from_place(&place);
-                    use_check_stmt(base_id.clone(), manager, stmt, bb, fn_name, body);
+                    // 对于 use_check，需要检查基础 local；直接传给 drop_in_place/ManuallyDrop::drop 的借用
+                    // 不是使用，再次 drop 由 drop_check 报告
+                    if !is_pointee_drop_argument(left, bb, body, tcx) {
+                        let base_id = extract_base_local_from_place(&place);
+                        use_check_stmt(base_id, manager, findings, stmt, bb, fn_name, body);
+                    }
                     
                     // 提取 local ID（支持多层嵌套）
                     let source_id = extract_local_from_place(&place);
                     
-                    // 确保 source_id 已注册
-                    if let Some(ref source) = source_id {
-                        manager.register(source.clone(), None);
+                    // 确保 source_id 已注册，并记录它被借用（离开作用域时引用失效）
+                    if let Some(source) = source_id {
+                        manager.register(source, None);
+                        manager.mark_borrowed(source);
                     }
                     
                     // 绑定引用源和目标
                     // 使用 left_base_id 作为绑定目标（保持兼容性）
-                    if let (Some(ref source), Some(ref target)) = (source_id, left_base_id) {
-                        if let Err(e) = manager.bind(source, target) {
-                            eprintln!("⚠️  Warning: bind failed in Ref {} -> {}: {}", source, target, e);
+                    if let (Some(source), Some(target)) = (source_id, left_base_id) {
+                        match manager.bind(source, target) {
+                            Ok(()) => manager.record_event(PathEventKind::Bind, target, Some(source), bb, stmt.source_info.span),
+                            Err(e) => eprintln!("⚠️  Warning: bind failed in Ref {} -> {}: {}", source, target, e),
                         }
                     }
                 }
@@ -245,23 +275,39 @@ pub fn detect_stmt(stmt: &Statement<'_>, manager: &mut BindingManager, bb: Basic
                     // ThreadLocalRef: 无 local use (全局线程本地)
                 }
                 Rvalue::RawPtr(_, place) => {
-                    // RawPtr: 获取原始指针
+                    // RawPtr: 获取原始指针（`&raw const x`、`addr_of!(x)`），与 Ref 一样绑定到指向的值
                     let id_opt = extract_local_from_place(&place);
-                    use_check_stmt(id_opt, manager, stmt, bb, fn_name, body);
+                    use_check_stmt(id_opt, manager, findings, stmt, bb, fn_name, body);
+                    if let Some(source) = id_opt {
+                        manager.register(source, None);
+                        manager.mark_borrowed(source);
+                    }
+                    // `&raw const (*r)`（如 `&x as *const T`）与 r 指向同一个值
+                    let source = match place.projection.as_slice() {
+                        [ProjectionElem::Deref] => Some(PlaceId::local(place.local.as_usize())),
+                        _ => id_opt,
+                    };
+                    bind_pointer(source, left_base_id, manager, bb, stmt);
                 }
-                Rvalue::Cast(_, op, _) => {
+                Rvalue::Cast(cast_kind, op, _) => {
                     // Cast: use op (e.g., a = b as i32)
                     // 可能涉及字段访问，使用 extract 更精确
                     let id_opt = extract_local_from_operand(&op);
-                    use_check_stmt(id_opt, manager, stmt, bb, fn_name, body);
+                    use_check_stmt(id_opt, manager, findings, stmt, bb, fn_name, body);
+
+                    // 引用的 unsize 强制转换（如 &mut [u8; 64] -> &mut [u8]）和指针之间的转换
+                    // （如 `&x as *const T`、`p as *mut U`）仍指向同一个值，绑定两者
+                    if is_pointer_cast(cast_kind, left, body) {
+                        bind_pointer(id_opt, left_base_id, manager, bb, stmt);
+                    }
                 }
                 Rvalue::BinaryOp(_, box (op1, op2)) => {
                     // BinaryOp (e.g., a = b + c): use op1 和 op2
                     // 可能涉及字段访问，使用 extract 更精确
                     let id1_opt = extract_local_from_operand(&op1);
-                    use_check_stmt(id1_opt, manager, stmt, bb, fn_name, body);
+                    use_check_stmt(id1_opt, manager, findings, stmt, bb, fn_name, body);
                     let id2_opt = extract_local_from_operand(&op2);
-                    use_check_stmt(id2_opt, manager, stmt, bb, fn_name, body);
+                    use_check_stmt(id2_opt, manager, findings, stmt, bb, fn_name, body);
                 }
                 Rvalue::NullaryOp(_, _) => {
                     // NullaryOp (e.g., BoxNew, Null): 无 Operand/Place use
@@ -270,44 +316,55 @@ pub fn detect_stmt(stmt: &Statement<'_>, manager: &mut BindingManager, bb: Basic
                     // UnaryOp (e.g., a = -b): use op
                     // 可能涉及字段访问，使用 extract 更精确
                     let id_opt = extract_local_from_operand(&op);
-                    use_check_stmt(id_opt, manager, stmt, bb, fn_name, body);
+                    use_check_stmt(id_opt, manager, findings, stmt, bb, fn_name, body);
                 }
                 Rvalue::Discriminant(place) => {
                     // Discriminant: use place (enum 标签)
                     let id_opt = extract_base_local_from_place(&place);
-                    use_check_stmt(id_opt, manager, stmt, bb, fn_name, body);
+                    use_check_stmt(id_opt, manager, findings, stmt, bb, fn_name, body);
                 }
-                Rvalue::Aggregate(_, fields) => {
+                Rvalue::Aggregate(kind, fields) => {
                     // Aggregate (struct/tuple/array init): fields 是 Vec<Operand>，每个可能 use
                     // 可能涉及字段访问，使用 extract 更精确
                     for field in fields {
                         let id_opt = extract_local_from_operand(&field);
-                        use_check_stmt(id_opt, manager, stmt, bb, fn_name, body);
+                        use_check_stmt(id_opt, manager, findings, stmt, bb, fn_name, body);
+                        mark_moved_out(field, manager);
                     }
+                    bind_pointer_fields(kind, &fields.raw, left, manager, bb, stmt, body);
                 }
                 Rvalue::ShallowInitBox(op, _) => {
                     // ShallowInitBox: use op (box init)
                     // 可能涉及字段访问，使用 extract 更精确
                     let id_opt = extract_local_from_operand(&op);
-                    use_check_stmt(id_opt, manager, stmt, bb, fn_name, body);
+                    use_check_stmt(id_opt, manager, findings, stmt, bb, fn_name, body);
                 }
                 Rvalue::CopyForDeref(place) => {
                     // CopyForDeref: use place (解引用 copy)
                     // 可能涉及字段访问，使用 extract 更精确
                     let id_opt = extract_local_from_place(&place);
-                    use_check_stmt(id_opt, manager, stmt, bb, fn_name, body);
+                    use_check_stmt(id_opt, manager, findings, stmt, bb, fn_name, body);
                 }
                 Rvalue::WrapUnsafeBinder(op, _) => {
                     // WrapUnsafeBinder: 包装不安全的 binder
                     let id_opt = extract_local_from_operand(&op);
-                    use_check_stmt(id_opt, manager, stmt, bb, fn_name, body);
+                    use_check_stmt(id_opt, manager, findings, stmt, bb, fn_name, body);
+                }
+            }
+
+            // 污点沿复制/计算传播（Move 和 Ref 已经通过 bind 合并了污点）
+            if let Some(target) = extract_base_local_from_place(left) {
+                if let Some(source) = rvalue_taint(rValue, manager) {
+                    manager.taint(target, source);
                 }
             }
         }
         StatementKind::FakeRead(_) => {}
         StatementKind::SetDiscriminant { .. } => {}
         StatementKind::StorageLive(_) => {}
-        StatementKind::StorageDead(_) => {}
+        StatementKind::StorageDead(local) => {
+            storage_dead(local.as_usize(), manager, findings, stmt, bb, stmt_index, fn_name, body);
+        }
         StatementKind::Retag(_, _) => {}
         StatementKind::PlaceMention(_) => {}
         StatementKind::AscribeUserType(_, _) => {}
@@ -320,6 +377,375 @@ pub fn detect_stmt(stmt: &Statement<'_>, manager: &mut BindingManager, bb: Basic
     }
 }
 
+/// 把指针 `target` 绑定到它指向的 `source`（RawPtr、指针复制和转换）
+fn bind_pointer(
+    source: Option<PlaceId>,
+    target: Option<PlaceId>,
+    manager: &mut BindingManager,
+    bb: BasicBlock,
+    stmt: &Statement<'_>,
+) {
+    let (Some(source), Some(target)) = (source, target) else {
+        return;
+    };
+    manager.register(source, None);
+    manager.register(target, None);
+    match manager.bind(source, target) {
+        Ok(()) => manager.record_event(PathEventKind::Bind, target, Some(source), bb, stmt.source_info.span),
+        Err(e) => eprintln!("⚠️  Warning: bind failed in pointer {} -> {}: {}", source, target, e),
+    }
+}
+
+/// place 是否是裸指针类型的 local
+/// `place` 是本 block 中 `drop_in_place`/`ManuallyDrop::drop` 调用的参数
+/// （如 `_13 = &mut _1; _12 = ManuallyDrop::<T>::drop(move _13)`）
+fn is_pointee_drop_argument<'tcx>(place: &Place<'tcx>, bb: BasicBlock, body: &Body<'tcx>, tcx: TyCtxt<'tcx>) -> bool {
+    let Some(local) = place.as_local() else { return false };
+    let TerminatorKind::Call { func, args, .. } = &body.basic_blocks[bb].terminator().kind else {
+        return false;
+    };
+    let TyKind::FnDef(def_id, _) = func.ty(body, tcx).kind() else { return false };
+    crate::ownership::drop_call(tcx, *def_id) == Some(DropCall::Pointee)
+        && args.first().and_then(|arg| arg.node.place()).and_then(|arg| arg.as_local()) == Some(local)
+}
+
+fn is_raw_pointer_place(place: &Place<'_>, body: &Body<'_>) -> bool {
+    place.as_local().is_some_and(|local| body.local_decls[local].ty.is_raw_ptr())
+}
+
+/// 结果仍指向同一个值的转换：引用的 unsize，以及转换成裸指针的指针转换
+fn is_pointer_cast(cast_kind: &CastKind, left: &Place<'_>, body: &Body<'_>) -> bool {
+    match cast_kind {
+        CastKind::PointerCoercion(PointerCoercion::Unsize, _) => true,
+        CastKind::PtrToPtr
+        | CastKind::Transmute
+        | CastKind::PointerCoercion(PointerCoercion::MutToConstPointer | PointerCoercion::ArrayToPointer, _) => {
+            is_raw_pointer_place(left, body)
+        }
+        _ => false,
+    }
+}
+
+/// 通过指向静态内存的指针写入指针（`(*_3) = move _2`，`_3` 是 `static mut` 变量的地址）：
+/// 被写入的指针逃逸到静态内存，它指向的栈内存离开作用域时报告
+fn record_global_store(
+    left: &Place<'_>,
+    source: &Place<'_>,
+    manager: &mut BindingManager,
+    bb: BasicBlock,
+    stmt: &Statement<'_>,
+) {
+    if !left.is_indirect_first_projection() || source.as_local().is_none() {
+        return;
+    }
+    let (target, pointer) = (PlaceId::local(left.local.as_usize()), PlaceId::local(source.local.as_usize()));
+    if manager.is_global(target) {
+        manager.register(pointer, None);
+        manager.record_event(PathEventKind::EscapeToGlobal, pointer, None, bb, stmt.source_info.span);
+    }
+}
+
+/// 通过指针写入（`(*_3) = copy _2`）：交出的所有权存到了指针指向的内存中，由那里负责释放
+fn store_released(left: &Place<'_>, source: &Place<'_>, manager: &mut BindingManager) {
+    if !left.is_indirect_first_projection() {
+        return;
+    }
+    if let Some(source) = extract_local_from_place(source) {
+        manager.clear_released(source);
+    }
+}
+
+/// 聚合（struct/tuple）中的指针字段与字段 place 绑定（`_0 = (move _3, copy _4)` 绑定 `_0.0` 和 `_3`），
+/// 返回值的字段指向被 drop 的值时可以在 Return 处发现
+fn bind_pointer_fields(
+    kind: &AggregateKind<'_>,
+    fields: &[Operand<'_>],
+    left: &Place<'_>,
+    manager: &mut BindingManager,
+    bb: BasicBlock,
+    stmt: &Statement<'_>,
+    body: &Body<'_>,
+) {
+    // enum 的字段属于某个 variant，不在这里处理
+    let Some(target) = left.as_local().filter(|&local| !body.local_decls[local].ty.is_enum()) else {
+        return;
+    };
+    if !matches!(kind, AggregateKind::Tuple | AggregateKind::Adt(..)) {
+        return;
+    }
+    for (idx, field) in fields.iter().enumerate() {
+        let source = field.place().and_then(|place| place.as_local());
+        if source.is_some_and(|local| has_pointer(body.local_decls[local].ty)) {
+            let field_id = PlaceId::local(target.as_usize()).project(PathElem::Field(idx as u32));
+            bind_pointer(source.map(|local| PlaceId::local(local.as_usize())), Some(field_id), manager, bb, stmt);
+        }
+    }
+}
+
+/// 类型本身是（或泛型参数中含有）引用或裸指针
+fn has_pointer(ty: Ty<'_>) -> bool {
+    ty.walk().any(|arg| arg.as_type().is_some_and(|ty| ty.is_ref() || ty.is_raw_ptr()))
+}
+
+/// Return：`_0`（或它的字段）绑定到在返回之前被 drop 或离开作用域的 local 时，报告悬垂的返回值
+///
+/// 被 move 进 `_0` 的 local（如 `_0 = move _5`）的字段一并检查；`_0` 本身不含指针时按普通的
+/// use-after-drop 检查（返回已经被 drop 的值）。`_0` 拥有的内存已经被另一个所有者释放时报告 double free。
+fn return_check(manager: &mut BindingManager, findings: &mut FindingCollector, term: &Terminator<'_>, bb: BasicBlock, fn_name: &str, body: &Body<'_>) {
+    let return_id = PlaceId::local(0);
+    manager.register(return_id, None);
+    if crate::config::get().is_enabled(Detector::Leak) {
+        leak_check(manager, findings, term, bb, fn_name, body);
+    }
+    // 返回的所有者拥有的内存已经被另一个所有者释放，调用者会再释放一次
+    if manager.is_dropped(return_id) {
+        if let Some(owner) = manager.freed_co_owner(return_id) {
+            if crate::config::get().is_enabled(Detector::UseAfterDrop) {
+                crate::report::report_double_free(fn_name, term, bb, return_id, owner, body, manager, findings);
+            }
+            return;
+        }
+    }
+    let mut returned = Vec::new();
+    if has_pointer(body.local_decls[RETURN_PLACE].ty) {
+        returned.push(return_id);
+    } else {
+        use_check_term(Some(return_id), manager, findings, term, bb, fn_name, body);
+    }
+    if !crate::config::get().is_enabled(Detector::UseAfterDrop) {
+        return;
+    }
+    let mut owners: Vec<PlaceId> = manager
+        .find_group(return_id)
+        .map(|(_, members)| members.into_iter().filter(|&id| id == id.base()).collect())
+        .unwrap_or_default();
+    owners.sort();
+    for owner in owners {
+        returned.extend(manager.field_places(owner));
+    }
+    for place in returned {
+        if manager.is_dropped(place) || manager.is_out_of_scope(place) {
+            crate::report::report_dangling_return(fn_name, term, bb, place, body, manager, findings);
+        }
+    }
+}
+
+/// Return：into_raw / `ManuallyDrop::new` 交出的所有权到返回时仍然没有去处（没有被取回、drop、
+/// 返回、move 走、传给其他函数或存到别处），它拥有的内存泄漏
+///
+/// 绑定到同一块内存的组只报告一次；字段指向它的结构体被 move 走（或返回）时也算有了去处。
+fn leak_check(manager: &mut BindingManager, findings: &mut FindingCollector, term: &Terminator<'_>, bb: BasicBlock, fn_name: &str, body: &Body<'_>) {
+    let mut reported = Vec::new();
+    for (id, span) in manager.released_places() {
+        let Some((root, members)) = manager.find_group(id) else {
+            continue;
+        };
+        if reported.contains(&root) {
+            continue;
+        }
+        let handed_off = members.iter().any(|&member| {
+            member.local_index() == 0
+                || (member != member.base() && manager.is_moved_out(PlaceId::local(member.local_index())))
+        });
+        if handed_off || manager.is_moved_out(id) || manager.is_dropped(id) || manager.is_global(id) {
+            continue;
+        }
+        reported.push(root);
+        crate::report::report_leak(fn_name, term, bb, id, span, body, manager, findings);
+    }
+}
+
+/// 作为调用参数传出的值带走了它所在组交出的所有权：裸指针（包括转换后的），或者与持有所有权的
+/// local 类型相同的值（如 move 出去的 `ManuallyDrop`）。只传引用（如格式化输出）不算
+fn hand_off_released(operand: &Operand<'_>, manager: &mut BindingManager, body: &Body<'_>) {
+    let Some(local) = operand.place().and_then(|place| place.as_local()) else {
+        return;
+    };
+    let id = PlaceId::local(local.as_usize());
+    let ty = body.local_decls[local].ty;
+    let Some((_, members)) = manager.find_group(id) else {
+        return;
+    };
+    let hands_off = ty.is_raw_ptr()
+        || members.iter().any(|&member| {
+            manager.released(member).is_some()
+                && body.local_decls[rustc_middle::mir::Local::from_usize(member.local_index())].ty == ty
+        });
+    if hands_off {
+        manager.clear_released(id);
+    }
+}
+
+/// 整个 local 被 move 走（赋值、聚合或作为调用参数）
+fn mark_moved_out(operand: &Operand<'_>, manager: &mut BindingManager) {
+    if let Operand::Move(place) = operand {
+        if let Some(local) = place.as_local() {
+            manager.set_moved_out(PlaceId::local(local.as_usize()), true);
+        }
+    }
+}
+
+/// into_raw/from_raw：返回值和第一个参数绑定到同一块内存
+///
+/// into_raw 的参数已经作为调用参数被 move 走，所有权只留在返回的裸指针上。from_raw 重新取得
+/// 所有权时，组内仍然拥有这块内存（需要 drop、没有被 move 走也没有被 drop）的其它 local
+/// 说明同一块内存会有两个所有者，报告 double-ownership。
+#[allow(clippy::too_many_arguments)]
+fn ownership_transfer<'tcx>(
+    call: &crate::ownership::OwnershipCall,
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+    term: &Terminator<'tcx>,
+    bb: BasicBlock,
+    fn_name: &str,
+    body: &Body<'tcx>,
+    tcx: TyCtxt<'tcx>,
+) {
+    let (Some(dest_id), Some(arg_id)) = (
+        extract_local_from_place(destination),
+        args.first().and_then(|arg| extract_local_from_operand(&arg.node)),
+    ) else {
+        return;
+    };
+    manager.register(dest_id, None);
+    manager.register(arg_id, None);
+    if let Err(e) = manager.bind(dest_id, arg_id) {
+        eprintln!("⚠️  Warning: bind failed in Call {} -> {}: {}", dest_id, arg_id, e);
+        return;
+    }
+    let kind = match call.transfer {
+        crate::ownership::Transfer::IntoRaw => PathEventKind::IntoRaw,
+        crate::ownership::Transfer::FromRaw => PathEventKind::FromRaw,
+    };
+    manager.record_event(kind, dest_id, Some(arg_id), bb, term.source_info.span);
+    match call.transfer {
+        // 交出的所有权由返回值持有，到函数返回时仍没有去处就是泄漏
+        crate::ownership::Transfer::IntoRaw if destination.as_local().is_some() => {
+            manager.set_released(dest_id, term.source_info.span);
+        }
+        crate::ownership::Transfer::IntoRaw => {}
+        crate::ownership::Transfer::FromRaw => {
+            manager.clear_released(dest_id);
+            if destination.as_local().is_some() {
+                manager.set_reclaimed(dest_id, true);
+            }
+        }
+    }
+
+    // 已经被 drop 的内存在上面的参数检查中按 use-after-drop 报告
+    if call.transfer != crate::ownership::Transfer::FromRaw
+        || !crate::config::get().is_enabled(Detector::UseAfterDrop)
+        || manager.is_dropped(arg_id)
+    {
+        return;
+    }
+    let typing_env = body.typing_env(tcx);
+    let mut owners: Vec<PlaceId> = manager
+        .find_group(dest_id)
+        .map(|(_, members)| {
+            members
+                .into_iter()
+                .filter(|&id| id == id.base() && id != dest_id.base())
+                .filter(|&id| {
+                    let ty = body.local_decls[rustc_middle::mir::Local::from_usize(id.local_index())].ty;
+                    ty.needs_drop(tcx, typing_env) && !manager.is_moved_out(id)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    owners.sort_by_key(|id| id.local_index());
+    if let Some(&owner) = owners.first() {
+        crate::report::report_double_ownership(fn_name, term, bb, dest_id, owner, &call.name, body, manager, findings);
+    }
+}
+
+/// StorageDead：local 离开作用域，指向它（或它的字段）的引用和裸指针随之失效
+///
+/// 只处理被借用过、值没有被 move 走的 local；类型中含有引用或裸指针的 local 本身只是借用者，
+/// 它离开作用域不影响被指向的值，跳过。离开作用域与 drop 分开记录（`storage_dead_group`），
+/// 之后通过指针的使用报告为 use-after-scope；已经写入静态内存的指针在这里报告。
+#[allow(clippy::too_many_arguments)]
+fn storage_dead(
+    local: usize,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+    stmt: &Statement<'_>,
+    bb: BasicBlock,
+    stmt_index: usize,
+    fn_name: &str,
+    body: &Body<'_>,
+) {
+    if has_pointer(body.local_decls[rustc_middle::mir::Local::from_usize(local)].ty) {
+        return;
+    }
+    if manager.is_moved_out(PlaceId::local(local)) {
+        return;
+    }
+    let span = stmt.source_info.span;
+    for place in manager.borrowed_places(PlaceId::local(local)) {
+        // 已经被 drop 或离开作用域的保留原来的位置
+        if manager.is_dropped(place) || manager.is_out_of_scope(place) {
+            continue;
+        }
+        let drop_info = crate::state::DropInfo {
+            dropped_by: PlaceId::local(local).to_string(),
+            location: crate::state::DropLocation::Statement { bb, span, stmt_index },
+            function_name: fn_name.to_string(),
+        };
+        manager.storage_dead_group(place, drop_info);
+        manager.record_event(PathEventKind::StorageDead, place, None, bb, span);
+
+        if !crate::config::get().is_enabled(Detector::UseAfterDrop) {
+            continue;
+        }
+        let Some((_, members)) = manager.find_group(place) else {
+            continue;
+        };
+        let escaped: Vec<PlaceId> = manager
+            .events()
+            .iter()
+            .filter(|event| event.kind == PathEventKind::EscapeToGlobal && members.contains(&event.local))
+            .map(|event| event.local)
+            .collect();
+        for pointer in escaped {
+            crate::report::report_escape_to_global(fn_name, stmt, bb, pointer, PlaceId::local(local), body, manager, findings);
+        }
+    }
+}
+
+/// 获取 place 的污点来源：先查完整 ID，再依次查它的 parent，直到基础 local（如 `*_8.1` 查 `*_8`、`_8`）
+fn place_taint(place: &Place<'_>, manager: &BindingManager) -> Option<String> {
+    let id = extract_local_from_place(place)?;
+    std::iter::successors(Some(id), |id| id.parent()).find_map(|id| manager.taint_of(id))
+}
+
+fn operand_taint(operand: &Operand<'_>, manager: &BindingManager) -> Option<String> {
+    match operand {
+        Operand::Copy(place) | Operand::Move(place) => place_taint(place, manager),
+        Operand::Constant(_) => None,
+    }
+}
+
+/// 获取右值读取的数据的污点来源（不会 bind 的复制/计算类右值）
+fn rvalue_taint(rvalue: &Rvalue<'_>, manager: &BindingManager) -> Option<String> {
+    match rvalue {
+        Rvalue::Use(op @ Operand::Copy(_))
+        | Rvalue::Repeat(op, _)
+        | Rvalue::Cast(_, op, _)
+        | Rvalue::UnaryOp(_, op)
+        | Rvalue::ShallowInitBox(op, _)
+        | Rvalue::WrapUnsafeBinder(op, _) => operand_taint(op, manager),
+        Rvalue::BinaryOp(_, box (op1, op2)) => {
+            operand_taint(op1, manager).or_else(|| operand_taint(op2, manager))
+        }
+        Rvalue::Aggregate(_, fields) => fields.iter().find_map(|field| operand_taint(field, manager)),
+        Rvalue::CopyForDeref(place) | Rvalue::RawPtr(_, place) => place_taint(place, manager),
+        _ => None,
+    }
+}
+
 /// 调试标志：是否输出详细调试信息（可通过环境变量 DEBUG_MIR=1 控制）
 fn is_debug_enabled() -> bool {
     std::env::var("DEBUG_MIR").is_ok()
@@ -336,39 +762,18 @@ fn is_debug_enabled() -> bool {
 /// - `*_21.0` → 检查 `_21` 和 `*_21.0`
 /// - `_21.0@deref` → 检查 `_21.0` 和 `_21.0@deref`
 fn check_deref_dependencies(
-    id: &str,
+    id: PlaceId,
     manager: &mut BindingManager
 ) -> Result<(), Vec<String>> {
     let mut errors = Vec::new();
     
-    // 提取所有前置解引用的基础ID
-    let base_id = id.trim_start_matches('*');
-    
-    if base_id != id && !base_id.is_empty() {
-        // 有前置解引用，检查基础ID
-        // 例如：id = "*_21", base_id = "_21"
-        // 或：id = "**_21", base_id = "_21"
-        
-        // 提取纯基础ID（去掉字段和后置deref）
-        let pure_base = base_id.split('.').next().unwrap_or(base_id)
-                                 .split('@').next().unwrap_or(base_id);
-        
-        if manager.is_dropped(pure_base) {
+    // 检查每次解引用之前的指针
+    // 例如：id = "*_21" 检查 "_21"，id = "_21.0@deref" 检查 "_21.0"
+    for base in id.deref_bases() {
+        if manager.is_dropped(base) {
             errors.push(format!(
                 "Cannot dereference {}: base pointer {} is dropped",
-                id, pure_base
-            ));
-        }
-    }
-    
-    // 检查后置解引用的基础部分
-    if id.contains("@deref") {
-        // 例如：id = "_21.0@deref"，需要检查 "_21.0"
-        let base_part = id.split("@deref").next().unwrap_or(id);
-        if !base_part.is_empty() && manager.is_dropped(base_part) {
-            errors.push(format!(
-                "Cannot dereference {}: base {} is dropped",
-                id, base_part
+                id, base
             ));
         }
     }
@@ -377,6 +782,11 @@ fn check_deref_dependencies(
     if manager.is_dropped(id) {
         errors.push(format!("Use after drop: {}", id));
     }
+
+    // 指向的栈内存已经离开作用域
+    if std::iter::once(id).chain(id.deref_bases()).any(|id| manager.is_out_of_scope(id)) {
+        errors.push(format!("Use after scope: {}", id));
+    }
     
     if errors.is_empty() {
         Ok(())
@@ -388,10 +798,13 @@ fn check_deref_dependencies(
 /// 统一的 use 检查函数（用于 Statement）
 /// 检查变量是否已被 drop，如果已 drop 则返回错误并打印 span
 /// 对于解引用（如 *_21），还会检查基础指针是否有效
-pub fn use_check_stmt(id_opt: Option<String>, manager: &mut BindingManager, stmt: &Statement<'_>, bb: BasicBlock, fn_name: &str, body: &Body<'_>) -> Result<(), String> {
-    if let Some(ref id) = id_opt {
+pub fn use_check_stmt(id_opt: Option<PlaceId>, manager: &mut BindingManager, findings: &mut FindingCollector, stmt: &Statement<'_>, bb: BasicBlock, fn_name: &str, body: &Body<'_>) -> Result<(), String> {
+    if let Some(id) = id_opt {
         // 确保已注册
-        manager.register(id.clone(), None);
+        manager.register(id, None);
+        if !crate::config::get().is_enabled(Detector::UseAfterDrop) {
+            return Ok(());
+        }
 
         // 检查是否被 drop（包括依赖检查）
         if is_debug_enabled() {
@@ -408,7 +821,7 @@ pub fn use_check_stmt(id_opt: Option<String>, manager: &mut BindingManager, stmt
         // 使用增强的依赖检查
         if let Err(errors) = check_deref_dependencies(id, manager) {
             // 报告第一个错误
-            crate::report::report_use_after_drop_stmt(fn_name, stmt, bb, id, body, manager);
+            crate::report::report_use_after_drop_stmt(fn_name, stmt, bb, id, body, manager, findings);
             return Err(errors.join("; "));
         }
     }
@@ -418,28 +831,34 @@ pub fn use_check_stmt(id_opt: Option<String>, manager: &mut BindingManager, stmt
 /// 统一的 use 检查函数（用于 Terminator）
 /// 检查变量是否已被 drop，如果已 drop 则返回错误并打印 span
 /// 对于解引用（如 *_21），还会检查基础指针是否有效
-pub fn use_check_term(id_opt: Option<String>, manager: &mut BindingManager, term: &Terminator<'_>, bb: BasicBlock, fn_name: &str, body: &Body<'_>) -> Result<(), String> {
-    if let Some(ref id) = id_opt {
+pub fn use_check_term(id_opt: Option<PlaceId>, manager: &mut BindingManager, findings: &mut FindingCollector, term: &Terminator<'_>, bb: BasicBlock, fn_name: &str, body: &Body<'_>) -> Result<(), String> {
+    if let Some(id) = id_opt {
         // 确保已注册
-        manager.register(id.clone(), None);
+        manager.register(id, None);
+        if !crate::config::get().is_enabled(Detector::UseAfterDrop) {
+            return Ok(());
+        }
 
         // 使用增强的依赖检查
         if let Err(errors) = check_deref_dependencies(id, manager) {
             // 报告第一个错误
-            crate::report::report_use_after_drop_term(fn_name, term, bb, id, body, manager);
+            crate::report::report_use_after_drop_term(fn_name, term, bb, id, body, manager, findings);
             return Err(errors.join("; "));
         }
     }
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn detect_terminator<'tcx>(
     term: &Terminator<'tcx>,
     manager: &mut BindingManager,
+    findings: &mut FindingCollector,
     body: &Body<'tcx>,
     tcx: TyCtxt<'tcx>,
     bb: BasicBlock,
-    fn_name: &str
+    fn_name: &str,
+    summaries: &SummaryMap<'tcx>,
 ) {
     match &term.kind {
         TerminatorKind::Goto { .. } => {
@@ -448,7 +867,7 @@ pub fn detect_terminator<'tcx>(
         TerminatorKind::SwitchInt { discr, .. } => {
             // SwitchInt: 基于整数值的条件跳转，discr 被使用
             let id_opt = extract_base_local_from_operand(discr);
-            use_check_term(id_opt, manager, term, bb, fn_name, body);
+            use_check_term(id_opt, manager, findings, term, bb, fn_name, body);
         }
         TerminatorKind::UnwindResume => {
             // UnwindResume: 异常恢复，不涉及 use/drop
@@ -458,11 +877,8 @@ pub fn detect_terminator<'tcx>(
         }
         TerminatorKind::Return => {
             // Return: 函数返回
-            // 检查返回值（通常存储在 local 0，即 _0）是否已被 drop
-            // 返回值在返回时会被 move，所以需要检查它是否已被 drop
-            // 注意：返回值总是存储在 local 0（_0）
-            let return_id = Some("_0".to_string());  // 返回值存储在 local 0
-            use_check_term(return_id, manager, term, bb, fn_name, body);
+            // 返回值总是存储在 local 0（_0），检查它（和它的字段）是否指向返回前被 drop 或离开作用域的 local
+            return_check(manager, findings, term, bb, fn_name, body);
         }
         TerminatorKind::Unreachable => {
             // Unreachable: 不可达代码，不涉及 use/drop
@@ -472,7 +888,7 @@ pub fn detect_terminator<'tcx>(
             let id = extract_local_from_place(place);
 
             if is_debug_enabled() {
-                if let Some(ref id_val) = id {
+                if let Some(id_val) = id {
                     let dropped_before = manager.is_dropped(id_val);
                     println!("  [DEBUG] Drop: local {} at {:?}, dropped_before={}",
                              id_val, term.source_info.span, dropped_before);
@@ -483,29 +899,40 @@ pub fn detect_terminator<'tcx>(
             drop_check(
                 id, 
                 manager, 
+                findings,
                 term, 
                 bb, 
                 fn_name, 
-                crate::state::DropTerminatorKind::DropTerminator
+                crate::state::DropTerminatorKind::DropTerminator,
+                body,
             );
         }
         TerminatorKind::Call { func, args, destination, .. } => {
             let ty = func.ty(body, tcx);
 
-            if let TyKind::FnDef(def_id, _args) = ty.kind() {
+            if let TyKind::FnDef(def_id, fn_args) = ty.kind() {
                 let name = tcx.item_name(*def_id);
+                let callee_path = tcx.def_path_str(*def_id);
 
-                // 检查函数名是否包含 "::drop"（如 std::mem::drop）
-                // 如果包含，将这个函数调用视为 drop 操作
-                let name_str = name.as_str();
-                let is_drop_function = name_str.contains("::drop");
+                // 标准库的 drop/forget 按 DefId 识别；其余函数按配置中的 drop 函数路径匹配
+                // 如果是 drop，将这个函数调用视为 drop 操作
+                let drop_call = crate::ownership::drop_call(tcx, *def_id);
+                let is_drop_function = match drop_call {
+                    // drop 引用、裸指针这类值不会 drop 它们指向的值
+                    Some(DropCall::Value) => args
+                        .first()
+                        .is_some_and(|arg| arg.node.ty(body, tcx).needs_drop(tcx, body.typing_env(tcx))),
+                    Some(DropCall::Pointee) => true,
+                    Some(DropCall::Forget) => false,
+                    None => is_drop_function(&callee_path),
+                };
 
                 if is_drop_function && !args.is_empty() {
                     // 提取第一个参数（完整 ID，包括解引用）
                     let arg = &args[0];
                     let arg_id = extract_local_from_operand(&arg.node);
 
-                    if let Some(ref id_str) = arg_id {
+                    if let Some(id_str) = arg_id {
                         if is_debug_enabled() {
                             let dropped_before = manager.is_dropped(id_str);
                             println!("  [DEBUG] Drop function call: local {} at {:?}, dropped_before={}",
@@ -514,40 +941,56 @@ pub fn detect_terminator<'tcx>(
 
                         // 直接调用 drop_check，让它统一处理所有情况（包括 double drop 检测）
                         if let Err(e) = drop_check(
-                            arg_id.clone(), 
+                            arg_id, 
                             manager, 
+                            findings,
                             term, 
                             bb, 
                             fn_name,
                             crate::state::DropTerminatorKind::DropFunctionCall { 
-                                function_name: name.to_string() 
-                            }
+                                function_name: callee_path.clone()
+                            },
+                            body,
                         ) {
                             eprintln!("⚠️  Warning: drop_check failed in Call: {}", e);
                         }
+                        // drop_in_place/ManuallyDrop::drop 释放的是参数指向的值，参数是引用时也一样
+                        if drop_call == Some(DropCall::Pointee) {
+                            manager.set_freed(id_str, true);
+                        }
                     }
                 }
 
-                // 使用黑名单检查函数名
+                // into_raw/from_raw 按所有权转移处理（包括绑定），不再走别名黑名单
+                let ownership = crate::ownership::classify(tcx, *def_id);
+
+                // 使用黑名单检查函数名；`Deref::deref`/`DerefMut::deref_mut` 按 trait 识别
                 let blacklist = get_blacklist();
-                if is_in_blacklist(name, blacklist) {
+                let aliases = crate::ownership::is_deref_method(tcx, *def_id) || is_in_blacklist(name, blacklist);
+                if ownership.is_none() && aliases {
                     if !args.is_empty() {
                         if let (Some(dest_id), Some(arg_id)) = (
                             extract_local_from_place(destination),
                             extract_local_from_operand(&args[0].node)
                         ) {
                             // 确保 destination 和 arg 都已注册（支持字段访问）
-                            manager.register(dest_id.clone(), None);
-                            manager.register(arg_id.clone(), None);
+                            manager.register(dest_id, None);
+                            manager.register(arg_id, None);
 
-                            if let Err(e) = manager.bind(&dest_id, &arg_id) {
-                                eprintln!("⚠️  Warning: bind failed in Call {} -> {}: {}", dest_id, arg_id, e);
+                            match manager.bind(dest_id, arg_id) {
+                                Ok(()) => manager.record_event(PathEventKind::Bind, dest_id, Some(arg_id), bb, term.source_info.span),
+                                Err(e) => eprintln!("⚠️  Warning: bind failed in Call {} -> {}: {}", dest_id, arg_id, e),
                             }
                         }
                     }
                    // println!("func name in blacklist: {:?}", name);
                 }
 
+                // ptr::write 先重新初始化目标，写入 drop_in_place 之后的位置不是 use-after-drop
+                if crate::ownership::is_overwrite(tcx, *def_id) {
+                    overwrite_check(*def_id, args, manager, findings, term, bb, fn_name, body, tcx);
+                }
+
                 // 检查函数调用参数
                 // 注意：对于引用参数（如 &mut T），我们检查的是引用指向的 local
                 // 如果这个 local 刚被重新赋值，它应该已经被恢复状态了
@@ -560,14 +1003,55 @@ pub fn detect_terminator<'tcx>(
                     let place = extract_base_local_from_operand(&arg.node);
                     // 在检查之前，确保状态是最新的
                     // 如果这个 local 在同一个基本块中被重新赋值，状态应该已经恢复了
-                    use_check_term(place, manager, term, bb, fn_name, body);
+                    use_check_term(place, manager, findings, term, bb, fn_name, body);
+                }
+                // 作为参数 move 进被调函数的值不再属于当前函数；返回值覆盖 destination
+                for arg in args.iter() {
+                    mark_moved_out(&arg.node, manager);
+                }
+                // 交出的所有权传给被调函数后由被调函数负责；只返回别名的函数和裸指针方法的 self
+                // 除外，所有权转移调用在 ownership_transfer 中处理
+                if drop_call == Some(DropCall::Forget) {
+                    // mem::forget 是有意的泄漏
+                    if let Some(arg_id) = args.first().and_then(|arg| extract_local_from_operand(&arg.node)) {
+                        manager.clear_released(arg_id);
+                    }
+                } else if ownership.is_none() {
+                    let alias_only = is_in_blacklist(name, blacklist) || crate::ownership::is_raw_pointer_method(tcx, *def_id);
+                    for arg in args.iter().skip(usize::from(alias_only)) {
+                        hand_off_released(&arg.node, manager, body);
+                    }
+                }
+                if destination.as_local().is_some() {
+                    if let Some(dest_id) = extract_base_local_from_place(destination) {
+                        manager.set_moved_out(dest_id, false);
+                        manager.set_reclaimed(dest_id, false);
+                        manager.set_freed(dest_id, false);
+                    }
+                }
+
+                if let Some(call) = ownership {
+                    ownership_transfer(&call, args, destination, manager, findings, term, bb, fn_name, body, tcx);
+                }
+
+                // 应用 source/sink/sanitizer 规则
+                apply_taint_rules(&callee_path, args, destination, manager, findings, term, bb, fn_name, body);
+
+                // 应用被调函数的摘要（跨函数的 drop/escape 效果）
+                let callee = crate::summary::resolve_callee(tcx, body, *def_id, fn_args);
+                if let Some(summary) = summaries.get(*def_id, callee) {
+                    let callee_name = match callee {
+                        Some(instance) => tcx.def_path_str_with_args(instance.def_id(), instance.args),
+                        None => tcx.def_path_str(*def_id),
+                    };
+                    apply_summary(summary, &callee_name, args, destination, manager, findings, term, bb, fn_name, body);
                 }
             }
         }
         TerminatorKind::Assert { cond, .. } => {
             // Assert: 断言检查，cond 被使用
             let id_opt = extract_base_local_from_operand(cond);
-            use_check_term(id_opt, manager, term, bb, fn_name, body);
+            use_check_term(id_opt, manager, findings, term, bb, fn_name, body);
         }
         TerminatorKind::InlineAsm { .. } => {
             // InlineAsm: 内联汇编，需要检查所有操作数
@@ -589,34 +1073,106 @@ pub fn detect_terminator<'tcx>(
     }
 }
 
-/// 检查 ID 是否是字段访问（如 _1.0, _1.1, (_1 as 0).0）
-fn is_field_access(id: &str) -> bool {
-    // 字段访问的特征：包含 "." 或 "("（枚举字段）
-    id.contains('.') || id.contains('(')
+/// `ptr::write(dst, value)`：dst 指向的 local 原来的值没有被 drop 就被覆盖，它拥有的内存泄漏
+///
+/// 只检查与写入的值类型相同、需要 drop、仍然持有值（没有被 move 走或 drop）的 local；写入之后
+/// 这个 local 重新被初始化。
+#[allow(clippy::too_many_arguments)]
+fn overwrite_check<'tcx>(
+    def_id: DefId,
+    args: &[Spanned<Operand<'tcx>>],
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+    term: &Terminator<'tcx>,
+    bb: BasicBlock,
+    fn_name: &str,
+    body: &Body<'tcx>,
+    tcx: TyCtxt<'tcx>,
+) {
+    let (Some(dst), Some(value)) = (args.first().and_then(|arg| extract_local_from_operand(&arg.node)), args.get(1)) else {
+        return;
+    };
+    let value_ty = value.node.ty(body, tcx);
+    let mut owners: Vec<PlaceId> = manager
+        .find_group(dst)
+        .map(|(_, members)| {
+            members
+                .into_iter()
+                .filter(|&id| id == id.base() && id != dst.base())
+                .filter(|&id| body.local_decls[rustc_middle::mir::Local::from_usize(id.local_index())].ty == value_ty)
+                .collect()
+        })
+        .unwrap_or_default();
+    owners.sort_by_key(|id| id.local_index());
+    let Some(&owner) = owners.first() else {
+        return;
+    };
+    let initialized = !manager.is_moved_out(owner) && !manager.is_dropped(owner);
+    if initialized
+        && crate::config::get().is_enabled(Detector::Leak)
+        && value_ty.needs_drop(tcx, body.typing_env(tcx))
+    {
+        let callee = if crate::ownership::is_raw_pointer_method(tcx, def_id) {
+            format!("<*mut T>::{}", tcx.item_name(def_id))
+        } else {
+            format!("ptr::{}", tcx.item_name(def_id))
+        };
+        crate::report::report_overwrite_leak(fn_name, term, bb, owner, &callee, body, manager, findings);
+    }
+    if !initialized {
+        manager.undrop_group(owner);
+        manager.set_moved_out(owner, false);
+        for member in manager.find_group(owner).map(|(_, members)| members).unwrap_or_default() {
+            manager.set_freed(member, false);
+        }
+        manager.record_event(PathEventKind::Reassign, owner, None, bb, term.source_info.span);
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn drop_check(
-    id_opt: Option<String>, 
+    id_opt: Option<PlaceId>, 
     manager: &mut BindingManager, 
+    findings: &mut FindingCollector,
     terminator: &Terminator<'_>, 
     bb: BasicBlock,
     fn_name: &str,
     drop_kind: crate::state::DropTerminatorKind,
+    body: &Body<'_>,
 ) -> Result<(), String> {
-    if let Some(ref id) = id_opt {
+    if let Some(id) = id_opt {
         // 确保已注册
-        manager.register(id.clone(), None);
+        manager.register(id, None);
+
+        // 同一块内存的另一个所有者（from_raw 取得所有权的一方或原来的所有者）已经把它释放了：
+        // 两个不同的所有者各 drop 一次是 double free，与 cleanup 路径上再次 drop 同一个值区分开
+        if manager.is_dropped(id) {
+            if let Some(owner) = manager.freed_co_owner(id) {
+                if crate::config::get().is_enabled(Detector::UseAfterDrop) {
+                    crate::report::report_double_free(fn_name, terminator, bb, id, owner, body, manager, findings);
+                }
+                return Ok(());
+            }
+        }
+
+        // 再次 drop 已经被 drop 的值：cleanup block（panic 时的 unwind 路径）上这是 MIR 的正常行为，
+        // 例如 `_7` 被绑定到 `(_5 as 1).0` 后，`(_5 as 1).0` 被 drop 时 `_7` 也视为已 drop，
+        // unwind 路径上会再次 drop `_7`；被 move 走的值的 drop 不会真正执行，之前只 drop 了指向它的
+        // 引用也没有释放它。其余情况是 double drop。
+        let allow_redrop =
+            body.basic_blocks[bb].is_cleanup || manager.is_moved_out(id.base()) || !manager.is_freed(id);
 
         // 直接获取该 local 的 state，检查它的 drop state
-        // 这样可以区分是否是 drop 完全同一个 local（cleanup 路径中的正常行为）
-        if let Some(state) = manager.states.get(id) {
+        if let Some(state) = manager.states.get(&id) {
             // 检查该 local 本身的 drop state（不是通过绑定关系传播的）
             if state.is_dropped {
-                // 这是 drop 完全同一个 local，可能是 cleanup 路径中的正常行为，允许
-                if is_debug_enabled() {
-                    println!("  [DEBUG] Allow drop: local {} is already dropped (same local, possibly cleanup path)", id);
+                if allow_redrop {
+                    if is_debug_enabled() {
+                        println!("  [DEBUG] Allow drop: local {} is already dropped (same local, cleanup path or moved out)", id);
+                    }
+                } else if crate::config::get().is_enabled(Detector::UseAfterDrop) {
+                    crate::report::report_double_drop(fn_name, terminator, bb, id, body, manager, findings);
                 }
-                // 允许，不报错
                 return Ok(());
             }
         }
@@ -629,7 +1185,7 @@ fn drop_check(
                 // 如果找不到 root，说明还没有绑定关系，直接 drop
                 // 创建drop位置信息
                 let drop_info = crate::state::DropInfo {
-                    dropped_by: id.clone(),
+                    dropped_by: id.to_string(),
                     location: crate::state::DropLocation::Terminator {
                         bb,
                         span: terminator.source_info.span,
@@ -638,40 +1194,29 @@ fn drop_check(
                     function_name: fn_name.to_string(),
                 };
                 manager.idrop_group_with_info(id, drop_info);
+                if !is_reference_local(id, body) {
+                    manager.set_freed(id, true);
+                }
+                manager.record_event(PathEventKind::Drop, id, None, bb, terminator.source_info.span);
                 return Ok(());
             }
         };
 
         // 压缩路径
-        crate::state::LocalState::compress_path(&mut manager.states, &path, &root_id);
+        crate::state::LocalState::compress_path(&mut manager.states, &path, root_id);
 
         // 检查 root 的 drop state
-        if crate::state::LocalState::get_root_dropped(&root_id, &manager.states) {
-            // 通过绑定关系已经被 drop
-            // 如果 root_id == id，说明是 drop 同一个 local，允许（可能是 cleanup 路径）
-            if root_id == *id {
+        if crate::state::LocalState::get_root_dropped(root_id, &manager.states) {
+            // 通过绑定关系已经被 drop（root_id == id 时是同一个 local）
+            if allow_redrop {
                 if is_debug_enabled() {
-                    println!("  [DEBUG] Allow drop: local {} is root and already dropped (possibly cleanup path)", id);
-                }
-                return Ok(());
-            }
-
-            // root_id != id，说明是通过绑定关系传播的
-            // 关键问题：如果该 local 通过绑定关系已经被 drop，再次 drop 可能是 cleanup 路径的正常行为
-            // 但是，如果该 local 本身也被 drop 了（state.is_dropped == true），那么应该允许
-            // 如果该 local 本身没有被 drop（state.is_dropped == false），但 root 被 drop 了，
-            // 这可能是误报，因为该 local 和 root 是同一个值（通过绑定关系）
-            // 
-            // 但是，我们无法区分是否是 cleanup 路径，所以这里应该允许
-            // 因为：如果 _7 被绑定到 (_5 as 1).0，当 (_5 as 1).0 被 drop 时，_7 也应该被视为已 drop
-            // 在 cleanup 路径中再次 drop _7 是正常的 MIR 行为
-            // 
-            // 注意：这可能会漏掉一些真正的 double drop，但根据错误信息，这是误报
-            if is_debug_enabled() {
-                println!("  [DEBUG] Allow drop: local {} is already dropped through binding (root: {}), possibly cleanup path", id, root_id);
-                if let Some((r_id, members)) = manager.find_group(id) {
-                    println!("   [DEBUG] Group root: {}, members: {:?}", r_id, members);
+                    println!("  [DEBUG] Allow drop: local {} is already dropped through binding (root: {}), cleanup path or moved out", id, root_id);
+                    if let Some((r_id, members)) = manager.find_group(id) {
+                        println!("   [DEBUG] Group root: {}, members: {:?}", r_id, members);
+                    }
                 }
+            } else if crate::config::get().is_enabled(Detector::UseAfterDrop) {
+                crate::report::report_double_drop(fn_name, terminator, bb, id, body, manager, findings);
             }
             return Ok(());
         }
@@ -684,7 +1229,7 @@ fn drop_check(
 
         // 创建drop位置信息
         let drop_info = crate::state::DropInfo {
-            dropped_by: id.clone(),
+            dropped_by: id.to_string(),
             location: crate::state::DropLocation::Terminator {
                 bb,
                 span: terminator.source_info.span,
@@ -693,37 +1238,164 @@ fn drop_check(
             function_name: fn_name.to_string(),
         };
         manager.idrop_group_with_info(id, drop_info);
+        if !is_reference_local(id, body) {
+            manager.set_freed(id, true);
+        }
+        manager.record_event(PathEventKind::Drop, id, None, bb, terminator.source_info.span);
     } else {
         return Err(format!("id not found in {:?}", terminator));
     }
     Ok(())
 }
 
+/// 类型是引用的整个 local：drop 它（如 `drop(&mut v)`）不会释放它指向的值；
+/// 裸指针只会传给 `drop_in_place` 这类真正 drop 指向的值的函数
+fn is_reference_local(id: PlaceId, body: &Body<'_>) -> bool {
+    id == id.base() && body.local_decls[rustc_middle::mir::Local::from_usize(id.local_index())].ty.is_ref()
+}
+
+/// 在调用点应用被调函数的摘要
+///
+/// - escape/alias 的参数：把返回值（destination）绑定到对应实参
+/// - drop 的参数：把对应实参所在的组标记为 dropped（记录为 CalleeDrop）
+#[allow(clippy::too_many_arguments)]
+fn apply_summary(
+    summary: &FnSummary,
+    callee_name: &str,
+    args: &[Spanned<Operand<'_>>],
+    destination: &Place<'_>,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+    term: &Terminator<'_>,
+    bb: BasicBlock,
+    fn_name: &str,
+    body: &Body<'_>,
+) {
+    let arg_id = |idx: usize| args.get(idx - 1).and_then(|arg| extract_local_from_operand(&arg.node));
+
+    if let Some(dest_id) = extract_local_from_place(destination) {
+        for &idx in summary.escaped_params.iter().chain(summary.aliased_params.iter()) {
+            if let Some(source) = arg_id(idx) {
+                manager.register(dest_id, None);
+                manager.register(source, None);
+                match manager.bind(source, dest_id) {
+                    Ok(()) => manager.record_event(PathEventKind::Bind, dest_id, Some(source), bb, term.source_info.span),
+                    Err(e) => eprintln!("⚠️  Warning: bind failed in summary of {} {} -> {}: {}", callee_name, source, dest_id, e),
+                }
+            }
+        }
+    }
+
+    if let (Some(source), Some(dest_id)) = (&summary.tainted_return, extract_local_from_place(destination)) {
+        manager.taint(dest_id, source.clone());
+    }
+
+    for &idx in &summary.dropped_params {
+        if let Some(id) = arg_id(idx) {
+            if is_debug_enabled() {
+                println!("  [DEBUG] Callee {} drops param {} (local {}) at {:?}", callee_name, idx, id, term.source_info.span);
+            }
+            let _ = drop_check(
+                Some(id),
+                manager,
+                findings,
+                term,
+                bb,
+                fn_name,
+                crate::state::DropTerminatorKind::CalleeDrop {
+                    function_name: callee_name.to_string(),
+                    param: idx,
+                },
+                body,
+            );
+        }
+    }
+}
+
+/// 在调用点应用污点规则
+///
+/// - sink：规则指定的实参被污染时报告
+/// - sanitizer：返回值不继承实参的污点
+/// - source：污染返回值或规则指定的实参（及其指向的 local）
+/// - 其他函数：任一实参被污染时返回值也被污染
+#[allow(clippy::too_many_arguments)]
+fn apply_taint_rules(
+    callee_path: &str,
+    args: &[Spanned<Operand<'_>>],
+    destination: &Place<'_>,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+    term: &Terminator<'_>,
+    bb: BasicBlock,
+    fn_name: &str,
+    body: &Body<'_>,
+) {
+    if !crate::config::get().is_enabled(Detector::Taint) {
+        return;
+    }
+    let rules = crate::taint::get_taint_rules();
+
+    if let Some(sink) = rules.sink(callee_path) {
+        let params: Vec<usize> = if sink.params.is_empty() {
+            (0..args.len()).collect()
+        } else {
+            sink.params.clone()
+        };
+        for idx in params {
+            let Some(arg) = args.get(idx) else { continue };
+            if let Some(source) = operand_taint(&arg.node, manager) {
+                let Some(id) = extract_local_from_operand(&arg.node) else { continue };
+                crate::report::report_tainted_sink(fn_name, term, bb, id, &source, callee_path, idx, body, manager, findings);
+            }
+        }
+    }
+
+    let Some(dest_id) = extract_local_from_place(destination) else { return };
+
+    if rules.is_sanitizer(callee_path) {
+        manager.untaint(dest_id);
+        return;
+    }
+
+    if let Some(source) = rules.source(callee_path) {
+        let label = format!("{} ({:?})", callee_path, term.source_info.span);
+        match source.target {
+            crate::taint::TaintTarget::Return => manager.taint(dest_id, label),
+            crate::taint::TaintTarget::Arg(idx) => {
+                if let Some(arg) = args.get(idx) {
+                    // 实参可能是 `(*_8)` 这样的解引用，同时污染它的基础 local
+                    if let Some(id) = extract_local_from_operand(&arg.node) {
+                        manager.taint(id, label.clone());
+                    }
+                    if let Some(base) = extract_base_local_from_operand(&arg.node) {
+                        manager.taint(base, label);
+                    }
+                }
+            }
+        }
+        return;
+    }
+
+    if let Some(source) = args.iter().find_map(|arg| operand_taint(&arg.node, manager)) {
+        manager.taint(dest_id, source);
+    }
+}
+
+/// 函数路径是否匹配配置中的 drop 函数（taint-ana.toml `[patterns] drop_functions`）
+fn is_drop_function(callee_path: &str) -> bool {
+    crate::config::get()
+        .patterns
+        .drop_functions
+        .iter()
+        .any(|pattern| callee_path.contains(pattern.as_str()))
+}
+
 //BlackList-----
-/// 获取黑名单（硬编码在代码中）
+/// 获取黑名单（taint-ana.toml `[patterns] alias_returning`，默认值见 `PatternsConfig`）
 /// 包含所有需要特殊处理的函数名子串
 fn get_blacklist() -> &'static HashSet<String> {
     BLACKLIST.get_or_init(|| {
-        let mut blacklist = HashSet::new();
-        
-        // 原始指针操作
-        blacklist.insert("as_mut_ptr".to_string());
-        blacklist.insert("as_ptr".to_string());
-        
-        // 引用转换
-        blacklist.insert("as_ref".to_string());
-        blacklist.insert("as_mut".to_string());
-        
-        // 原始指针构造
-        blacklist.insert("from_raw_parts".to_string());
-        blacklist.insert("into_raw".to_string());
-        blacklist.insert("from_raw".to_string());
-        blacklist.insert("_as_raw".to_string());
-        
-        // 解引用操作
-        blacklist.insert("::deref".to_string());
-        
-        blacklist
+        crate::config::get().patterns.alias_returning.iter().cloned().collect()
     })
 }
 
@@ -1,9 +1,14 @@
 use rustc_middle::mir::{BasicBlock, Body};
-use std::collections::{HashSet, HashMap};
+use std::collections::{BTreeSet, HashSet, HashMap};
+use std::time::{Duration, Instant};
+use crate::feasibility::{assume, feasible_successors};
+use crate::options::{TraversalEngine, UnwindPaths};
 use crate::state::BindingManager;
+use serde::Deserialize;
 
-/// DFS 配置结构体，控制遍历行为
-#[derive(Clone, Debug)]
+/// DFS 配置结构体，控制遍历行为（对应 taint-ana.toml 的 `[dfs]`）
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct DfsConfig {
     /// 记录的前序节点数量
     /// - 0: 和现有逻辑一样，每个 block 只访问一次
@@ -11,7 +16,20 @@ pub struct DfsConfig {
     pub k_predecessor: usize,
     
     /// 单个 block 的最大访问次数（防止无限循环）
+    /// worklist 引擎中达到上限的循环头会被加宽后再访问一次
     pub max_visits_per_block: usize,
+
+    /// 遍历引擎：`dfs`（逐条路径）或 `worklist`（不动点迭代）
+    pub engine: TraversalEngine,
+
+    /// panic 时的 unwind 路径（cleanup block）：`include`、`skip`（不走 unwind 出边）或 `only`
+    pub unwind: UnwindPaths,
+
+    /// 单个函数的时间预算（毫秒）；用完后停止遍历，结果标记为 timed out
+    pub time_budget_ms: Option<u64>,
+
+    /// 单个函数最多访问的 block 次数；用完后停止遍历，结果标记为 truncated
+    pub step_budget: Option<usize>,
 }
 
 impl Default for DfsConfig {
@@ -19,10 +37,67 @@ impl Default for DfsConfig {
         Self {
             k_predecessor: 2,
             max_visits_per_block: 10,  // 默认最多访问 10 次
+            engine: TraversalEngine::Dfs,
+            unwind: UnwindPaths::Include,
+            time_budget_ms: None,
+            step_budget: None,
+        }
+    }
+}
+
+/// 单个函数的分析结果是否覆盖了所有路径
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnalysisOutcome {
+    /// 遍历正常结束
+    #[default]
+    Complete,
+    /// 步数预算用完，或有 block 因达到 `max_visits_per_block` 被跳过
+    Truncated,
+    /// 时间预算用完
+    TimedOut,
+}
+
+impl AnalysisOutcome {
+    pub fn name(self) -> &'static str {
+        match self {
+            AnalysisOutcome::Complete => "complete",
+            AnalysisOutcome::Truncated => "truncated",
+            AnalysisOutcome::TimedOut => "timed out",
         }
     }
 }
 
+/// 时间/步数预算，在每次访问 block 之前检查
+struct Budget {
+    deadline: Option<Instant>,
+    max_steps: Option<usize>,
+}
+
+impl Budget {
+    fn new(config: &DfsConfig) -> Self {
+        Self {
+            deadline: config.time_budget_ms.map(|ms| Instant::now() + Duration::from_millis(ms)),
+            max_steps: config.step_budget,
+        }
+    }
+
+    /// 预算是否已经用完；第一次用完时在 stats 中记录原因
+    fn is_exhausted(&self, stats: &mut DfsStats) -> bool {
+        if stats.timed_out || stats.step_budget_exhausted {
+            return true;
+        }
+        if self.max_steps.is_some_and(|max| stats.successful_visits >= max) {
+            stats.step_budget_exhausted = true;
+            return true;
+        }
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            stats.timed_out = true;
+            return true;
+        }
+        false
+    }
+}
+
 /// 路径上下文结构体，记录当前遍历的路径信息
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct PathContext {
@@ -62,6 +137,9 @@ pub struct VisitState {
     /// 配置
     config: DfsConfig,
     
+    /// 时间/步数预算
+    budget: Budget,
+    
     /// 统计信息
     stats: DfsStats,
 }
@@ -81,11 +159,51 @@ pub struct DfsStats {
     /// 因达到访问上限被跳过的次数
     pub skipped_max_visits: usize,
     
+    /// 因分支条件不可能成立被剪掉的 SwitchInt 出边数
+    pub infeasible_edges: usize,
+    
     /// 访问过的唯一路径数量
     pub unique_paths: usize,
     
     /// 访问过的唯一 block 数量
     pub unique_blocks: usize,
+    
+    /// 时间预算用完，遍历提前结束
+    pub timed_out: bool,
+    
+    /// 步数预算用完，遍历提前结束
+    pub step_budget_exhausted: bool,
+}
+
+impl DfsStats {
+    /// 根据统计信息判断遍历是否完整
+    pub fn outcome(&self) -> AnalysisOutcome {
+        if self.timed_out {
+            AnalysisOutcome::TimedOut
+        } else if self.step_budget_exhausted || self.skipped_max_visits > 0 {
+            AnalysisOutcome::Truncated
+        } else {
+            AnalysisOutcome::Complete
+        }
+    }
+
+    /// 遍历不完整的原因；完整时返回 None
+    pub fn incomplete_reason(&self, config: &DfsConfig) -> Option<String> {
+        let reason = match self.outcome() {
+            AnalysisOutcome::Complete => return None,
+            AnalysisOutcome::TimedOut => {
+                format!("time budget of {} ms exhausted", config.time_budget_ms.unwrap_or_default())
+            }
+            AnalysisOutcome::Truncated if self.step_budget_exhausted => {
+                format!("step budget of {} block visits exhausted", config.step_budget.unwrap_or_default())
+            }
+            AnalysisOutcome::Truncated => format!(
+                "max_visits_per_block ({}) reached, {} visits skipped",
+                config.max_visits_per_block, self.skipped_max_visits
+            ),
+        };
+        Some(format!("{} after {} block visits", reason, self.successful_visits))
+    }
 }
 
 impl VisitState {
@@ -93,6 +211,7 @@ impl VisitState {
         Self {
             visited_paths: HashSet::new(),
             visit_counts: HashMap::new(),
+            budget: Budget::new(&config),
             config,
             stats: DfsStats::default(),
         }
@@ -103,6 +222,11 @@ impl VisitState {
     pub fn should_visit(&mut self, block: BasicBlock, context: &PathContext) -> bool {
         self.stats.total_visit_attempts += 1;
         
+        // 检查 0: 时间/步数预算用完后不再访问任何 block
+        if self.budget.is_exhausted(&mut self.stats) {
+            return false;
+        }
+        
         // 检查 1: 访问次数是否超过上限
         let count = self.visit_counts.get(&block).copied().unwrap_or(0);
         if count >= self.config.max_visits_per_block {
@@ -135,34 +259,6 @@ impl VisitState {
         
         true
     }
-    
-    /// 获取统计信息
-    pub fn get_stats(&self) -> &DfsStats {
-        &self.stats
-    }
-    
-    /// 打印统计信息
-    pub fn print_stats(&self, func_name: &str) {
-        if std::env::var("TAINT_ANA_DFS_STATS").is_ok() {
-            println!("\n=== DFS Statistics for {} ===", func_name);
-            println!("  Config: k={}, max_visits={}", 
-                     self.config.k_predecessor, 
-                     self.config.max_visits_per_block);
-            println!("  Total visit attempts: {}", self.stats.total_visit_attempts);
-            println!("  Successful visits: {}", self.stats.successful_visits);
-            println!("  Skipped (duplicate path): {}", self.stats.skipped_duplicate_path);
-            println!("  Skipped (max visits): {}", self.stats.skipped_max_visits);
-            println!("  Unique paths explored: {}", self.stats.unique_paths);
-            println!("  Unique blocks visited: {}", self.stats.unique_blocks);
-            
-            // 计算路径爆炸因子
-            if self.stats.unique_blocks > 0 {
-                let explosion_factor = self.stats.unique_paths as f64 / self.stats.unique_blocks as f64;
-                println!("  Path explosion factor: {:.2}x", explosion_factor);
-            }
-            println!("================================\n");
-        }
-    }
 }
 
 
@@ -201,7 +297,7 @@ pub fn dfs_visit<'tcx>(
 /// # 参数
 /// - `body`: MIR body
 /// - `start`: 起始 BasicBlock
-/// - `manager`: 绑定管理器（在分支时会保存和恢复状态）
+/// - `manager`: 绑定管理器（在分支时记录回溯点，每个分支开始前回溯）
 /// - `config`: DFS 配置（k 值、最大访问次数等）
 /// - `visitor`: 访问器函数，接收 (BasicBlock, &mut BindingManager, &PathContext)
 /// 
@@ -231,20 +327,27 @@ pub fn dfs_visit_with_manager_ex<'tcx>(
             return;
         }
         
-        // 调用访问函数
+        // 调用访问函数（先记录路径，报告中的 codeFlow 使用）
+        manager.enter_block(idx);
         visitor(idx, manager, path_context);
         
         let block = &body.basic_blocks[idx];
         if let Some(ref terminator) = block.terminator {
-            let successors: Vec<_> = terminator.successors().collect();
+            // 跳过在当前路径上不可能走的 SwitchInt 出边
+            let successors = feasible_successors(body, idx, terminator, manager);
+            visit_state.stats.infeasible_edges += terminator.successors().count() - successors.len();
+            let successors = skip_unwind_edges(body, successors, config);
             
-            // 分支处理（保存状态）
+            // 分支处理（记录回溯点，每个分支开始前撤销上一个分支的修改）
             if successors.len() > 1 {
-                let saved_manager = manager.clone();
+                let checkpoint = manager.checkpoint();
                 
-                for succ in successors {
-                    // 每个分支从保存的状态开始
-                    *manager = saved_manager.clone();
+                for (succ, condition) in successors {
+                    // 每个分支从分支前的状态开始
+                    manager.rollback(&checkpoint);
+                    if let Some(condition) = condition {
+                        assume(manager, condition);
+                    }
                     
                     // 更新路径上下文（添加当前 block）
                     let mut new_context = path_context.clone();
@@ -252,9 +355,13 @@ pub fn dfs_visit_with_manager_ex<'tcx>(
                     
                     dfs(body, succ, visit_state, &mut new_context, manager, config, visitor);
                 }
+                manager.release(checkpoint);
             } else {
                 // 单后继：直接继续，更新路径上下文
-                for succ in successors {
+                for (succ, condition) in successors {
+                    if let Some(condition) = condition {
+                        assume(manager, condition);
+                    }
                     path_context.push(idx, config.k_predecessor);
                     dfs(body, succ, visit_state, path_context, manager, config, visitor);
                 }
@@ -268,6 +375,127 @@ pub fn dfs_visit_with_manager_ex<'tcx>(
     visit_state.stats.clone()
 }
 
+/// worklist 不动点遍历
+///
+/// 每个 block 保存一个入口状态，按逆后序从 worklist 中取出 block，用入口状态的副本调用 visitor，
+/// 再把出口状态 join 到各个后继的入口状态；入口状态改变的后继重新加入 worklist。
+/// SwitchInt 只 join 到可能走的出边，每条出边先假设对应的分支条件成立（见 `feasibility`）。
+/// join 后只在部分路径上被 drop 的组变为 maybe dropped（见 `BindingManager::join`）。
+///
+/// 一个 block 的访问次数达到 `max_visits_per_block` 后，它的入口状态被加宽
+/// （`BindingManager::widen`）并最后访问一次，之后不再重新加入 worklist。
+/// 时间/步数预算用完时直接停止迭代。
+///
+/// visitor 的签名与 `dfs_visit_with_manager_ex` 相同；PathContext 为空（k = 0）。
+pub fn worklist_visit_with_manager<'tcx>(
+    body: &Body<'tcx>,
+    start: BasicBlock,
+    manager: &mut BindingManager,
+    config: DfsConfig,
+    visitor: &mut impl FnMut(BasicBlock, &mut BindingManager, &PathContext),
+) -> DfsStats {
+    let mut stats = DfsStats::default();
+    let budget = Budget::new(&config);
+    let context = PathContext::new(0);
+
+    // 逆后序作为优先级：汇合点在其前驱之后处理，循环体在回边 join 之前处理完
+    let mut priority: HashMap<BasicBlock, usize> = body
+        .basic_blocks
+        .reverse_postorder()
+        .iter()
+        .enumerate()
+        .map(|(order, bb)| (*bb, order))
+        .collect();
+    let mut order_of = |bb: BasicBlock| {
+        let next = priority.len();
+        *priority.entry(bb).or_insert(next)
+    };
+
+    let mut entry_states: HashMap<BasicBlock, BindingManager> = HashMap::new();
+    let mut visit_counts: HashMap<BasicBlock, usize> = HashMap::new();
+    let mut widened: HashSet<BasicBlock> = HashSet::new();
+    let mut worklist: BTreeSet<(usize, BasicBlock)> = BTreeSet::new();
+
+    entry_states.insert(start, manager.clone());
+    worklist.insert((order_of(start), start));
+
+    while let Some((_, idx)) = worklist.pop_first() {
+        stats.total_visit_attempts += 1;
+        if budget.is_exhausted(&mut stats) {
+            break;
+        }
+        stats.successful_visits += 1;
+        *visit_counts.entry(idx).or_insert(0) += 1;
+
+        let mut state = entry_states[&idx].clone();
+        state.enter_block(idx);
+        visitor(idx, &mut state, &context);
+
+        let Some(ref terminator) = body.basic_blocks[idx].terminator else {
+            continue;
+        };
+        let successors = feasible_successors(body, idx, terminator, &state);
+        stats.infeasible_edges += terminator.successors().count() - successors.len();
+        let successors = skip_unwind_edges(body, successors, &config);
+        for (succ, condition) in successors {
+            // SwitchInt 的每条出边带着各自的分支条件
+            let edge_state = condition.map(|condition| {
+                let mut edge_state = state.clone();
+                assume(&mut edge_state, condition);
+                edge_state
+            });
+            let out = edge_state.as_ref().unwrap_or(&state);
+            let changed = match entry_states.get_mut(&succ) {
+                Some(entry) => entry.join(out),
+                None => {
+                    entry_states.insert(succ, out.clone());
+                    true
+                }
+            };
+            if !changed {
+                continue;
+            }
+            let visits = visit_counts.get(&succ).copied().unwrap_or(0);
+            if visits < config.max_visits_per_block {
+                worklist.insert((order_of(succ), succ));
+            } else if widened.insert(succ) {
+                if let Some(entry) = entry_states.get_mut(&succ) {
+                    entry.widen();
+                }
+                worklist.insert((order_of(succ), succ));
+            } else {
+                stats.skipped_max_visits += 1;
+            }
+        }
+    }
+
+    stats.unique_blocks = visit_counts.len();
+    stats.unique_paths = visit_counts.len();
+    stats
+}
+
+/// `unwind = "skip"` 时去掉进入 cleanup block 的出边（panic 时的 unwind 路径）
+fn skip_unwind_edges<T>(body: &Body<'_>, successors: Vec<(BasicBlock, T)>, config: &DfsConfig) -> Vec<(BasicBlock, T)> {
+    if config.unwind != UnwindPaths::Skip {
+        return successors;
+    }
+    successors.into_iter().filter(|(succ, _)| !body.basic_blocks[*succ].is_cleanup).collect()
+}
+
+/// 按 `config.engine` 选择遍历引擎
+pub fn visit_with_engine<'tcx>(
+    body: &Body<'tcx>,
+    start: BasicBlock,
+    manager: &mut BindingManager,
+    config: DfsConfig,
+    visitor: &mut impl FnMut(BasicBlock, &mut BindingManager, &PathContext),
+) -> DfsStats {
+    match config.engine {
+        TraversalEngine::Dfs => dfs_visit_with_manager_ex(body, start, manager, config, visitor),
+        TraversalEngine::Worklist => worklist_visit_with_manager(body, start, manager, config, visitor),
+    }
+}
+
 /// DFS遍历，在遇到分支时保存和恢复manager状态
 /// 
 /// 这是兼容性包装函数，内部调用 `dfs_visit_with_manager_ex` with k=0
@@ -289,6 +517,11 @@ pub fn dfs_visit_with_manager<'tcx>(
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    fn l(local: usize) -> PlaceId {
+        PlaceId::local(local)
+    }
+    use crate::place::PlaceId;
     use crate::state::BindingManager;
 
     /// 测试 DFS 在分支时能正确保存和恢复状态
@@ -302,51 +535,51 @@ mod tests {
         let mut manager = BindingManager::new("test_func");
         
         // 初始状态：注册一些 locals
-        manager.register("_1".to_string(), None);
-        manager.register("_2".to_string(), None);
-        manager.register("_3".to_string(), None);
+        manager.register(l(1), None);
+        manager.register(l(2), None);
+        manager.register(l(3), None);
         
         // 在分支前进行一些操作
-        manager.bind("_1", "_2").unwrap();
-        manager.idrop_group("_1");
+        manager.bind(l(1), l(2)).unwrap();
+        manager.idrop_group(l(1));
         
         // 保存状态（模拟分支前的状态保存）
         let mut saved_state = &mut manager;
         
         // 验证保存的状态
-        assert!(saved_state.is_dropped("_1"));
-        assert!(saved_state.is_dropped("_2")); // 因为绑定关系
+        assert!(saved_state.is_dropped(l(1)));
+        assert!(saved_state.is_dropped(l(2))); // 因为绑定关系
         
         // 模拟分支 1：修改状态
         let mut branch1_state = saved_state.clone();
-        branch1_state.register("_4".to_string(), None);
-        branch1_state.bind("_3", "_4").unwrap();
-        branch1_state.idrop_group("_3");
+        branch1_state.register(l(4), None);
+        branch1_state.bind(l(3), l(4)).unwrap();
+        branch1_state.idrop_group(l(3));
         
         // 验证分支 1 的状态
-        assert!(branch1_state.is_dropped("_3"));
-        assert!(branch1_state.is_dropped("_4"));
-        assert!(branch1_state.is_dropped("_1")); // 从保存状态继承
-        assert!(branch1_state.is_dropped("_2")); // 从保存状态继承
+        assert!(branch1_state.is_dropped(l(3)));
+        assert!(branch1_state.is_dropped(l(4)));
+        assert!(branch1_state.is_dropped(l(1))); // 从保存状态继承
+        assert!(branch1_state.is_dropped(l(2))); // 从保存状态继承
         
         // 模拟分支 2：从保存的状态开始（回溯）
         let mut branch2_state = saved_state.clone();
-        branch2_state.register("_5".to_string(), None);
-        branch2_state.bind("_3", "_5").unwrap();
+        branch2_state.register(l(5), None);
+        branch2_state.bind(l(3), l(5)).unwrap();
         // 注意：分支 2 没有 drop _3
         
         // 验证分支 2 的状态（应该从保存状态开始，不受分支 1 影响）
-        assert!(!branch2_state.is_dropped("_3")); // 分支 2 没有 drop
-        assert!(!branch2_state.is_dropped("_5")); // 分支 2 没有 drop
-        assert!(branch2_state.is_dropped("_1")); // 从保存状态继承
-        assert!(branch2_state.is_dropped("_2")); // 从保存状态继承
+        assert!(!branch2_state.is_dropped(l(3))); // 分支 2 没有 drop
+        assert!(!branch2_state.is_dropped(l(5))); // 分支 2 没有 drop
+        assert!(branch2_state.is_dropped(l(1))); // 从保存状态继承
+        assert!(branch2_state.is_dropped(l(2))); // 从保存状态继承
         
         // 验证原始保存状态没有被修改
-        assert!(saved_state.is_dropped("_1"));
-        assert!(saved_state.is_dropped("_2"));
-        assert!(!saved_state.is_dropped("_3")); // 保存时 _3 没有被 drop
-        assert!(!saved_state.states.contains_key("_4")); // 保存时 _4 不存在
-        assert!(!saved_state.states.contains_key("_5")); // 保存时 _5 不存在
+        assert!(saved_state.is_dropped(l(1)));
+        assert!(saved_state.is_dropped(l(2)));
+        assert!(!saved_state.is_dropped(l(3))); // 保存时 _3 没有被 drop
+        assert!(!saved_state.states.contains_key(&l(4))); // 保存时 _4 不存在
+        assert!(!saved_state.states.contains_key(&l(5))); // 保存时 _5 不存在
     }
     
     /// 测试多个分支时，每个分支都从相同的初始状态开始
@@ -355,9 +588,9 @@ mod tests {
         let mut manager = BindingManager::new("test_func");
         
         // 初始状态
-        manager.register("_1".to_string(), None);
-        manager.register("_2".to_string(), None);
-        manager.bind("_1", "_2").unwrap();
+        manager.register(l(1), None);
+        manager.register(l(2), None);
+        manager.bind(l(1), l(2)).unwrap();
         
         // 保存状态
         let mut saved_state = manager.clone();
@@ -366,32 +599,32 @@ mod tests {
         let mut branches = Vec::new();
         for i in 0..5 {
             let mut branch_state = saved_state.clone();
-            let local_id = format!("_{}", 10 + i);
-            branch_state.register(local_id.clone(), None);
-            branch_state.bind("_1", &local_id).unwrap();
-            branch_state.idrop_group(&local_id);
+            let local_id = l(10 + i);
+            branch_state.register(local_id, None);
+            branch_state.bind(l(1), local_id).unwrap();
+            branch_state.idrop_group(local_id);
             branches.push(branch_state);
         }
         
         // 验证每个分支都从相同的初始状态开始
         for (idx, branch) in branches.iter_mut().enumerate() {
             // 每个分支都应该有相同的初始绑定关系
-            let (_root1, members1) = branch.find_group("_1").unwrap();
-            assert!(members1.contains(&"_1".to_string()));
-            assert!(members1.contains(&"_2".to_string()));
+            let (_root1, members1) = branch.find_group(l(1)).unwrap();
+            assert!(members1.contains(&l(1)));
+            assert!(members1.contains(&l(2)));
             
             // 每个分支都有自己的新 local（10+i）
-            let local_id = format!("_{}", 10 + idx);
+            let local_id = l(10 + idx);
             assert!(branch.states.contains_key(&local_id));
-            assert!(branch.is_dropped(&local_id));
+            assert!(branch.is_dropped(local_id));
         }
         
         // 验证保存的状态没有被修改
-        assert_eq!(saved_state.states.len(), 2); // 只有 _1 和 _2
-        let (_root, members) = saved_state.find_group("_1").unwrap();
+        assert_eq!(saved_state.states.keys().count(), 2); // 只有 _1 和 _2
+        let (_root, members) = saved_state.find_group(l(1)).unwrap();
         assert_eq!(members.len(), 2);
-        assert!(members.contains(&"_1".to_string()));
-        assert!(members.contains(&"_2".to_string()));
+        assert!(members.contains(&l(1)));
+        assert!(members.contains(&l(2)));
     }
     
     /// 测试分支退回后可以回溯到保存的状态
@@ -400,45 +633,45 @@ mod tests {
         let mut manager = BindingManager::new("test_func");
         
         // 初始状态
-        manager.register("_1".to_string(), None);
-        manager.register("_2".to_string(), None);
-        manager.bind("_1", "_2").unwrap();
+        manager.register(l(1), None);
+        manager.register(l(2), None);
+        manager.bind(l(1), l(2)).unwrap();
         
         // 保存状态 A
         let state_a = manager.clone();
         
         // 继续操作
-        manager.register("_3".to_string(), None);
-        manager.bind("_1", "_3").unwrap();
-        manager.idrop_group("_1");
+        manager.register(l(3), None);
+        manager.bind(l(1), l(3)).unwrap();
+        manager.idrop_group(l(1));
         
         // 保存状态 B
         let state_b = manager.clone();
         
         // 模拟分支：从状态 A 开始
         let mut branch_from_a = state_a.clone();
-        branch_from_a.register("_4".to_string(), None);
-        branch_from_a.bind("_2", "_4").unwrap();
+        branch_from_a.register(l(4), None);
+        branch_from_a.bind(l(2), l(4)).unwrap();
         
         // 模拟分支：从状态 B 开始
         let mut branch_from_b = state_b.clone();
-        branch_from_b.register("_5".to_string(), None);
-        branch_from_b.bind("_3", "_5").unwrap();
+        branch_from_b.register(l(5), None);
+        branch_from_b.bind(l(3), l(5)).unwrap();
         
         // 验证分支从 A 的状态（回溯）
-        assert!(!branch_from_a.is_dropped("_1")); // 状态 A 时 _1 没有被 drop
-        assert!(!branch_from_a.is_dropped("_2")); // 状态 A 时 _2 没有被 drop
-        assert!(!branch_from_a.states.contains_key("_3")); // 状态 A 时 _3 不存在
+        assert!(!branch_from_a.is_dropped(l(1))); // 状态 A 时 _1 没有被 drop
+        assert!(!branch_from_a.is_dropped(l(2))); // 状态 A 时 _2 没有被 drop
+        assert!(!branch_from_a.states.contains_key(&l(3))); // 状态 A 时 _3 不存在
         
         // 验证分支从 B 的状态
-        assert!(branch_from_b.is_dropped("_1")); // 状态 B 时 _1 已经被 drop
-        assert!(branch_from_b.is_dropped("_2")); // 状态 B 时 _2 因为绑定关系也被 drop
-        assert!(branch_from_b.is_dropped("_3")); // 状态 B 时 _3 因为绑定关系也被 drop
-        assert!(branch_from_b.states.contains_key("_3")); // 状态 B 时 _3 存在
+        assert!(branch_from_b.is_dropped(l(1))); // 状态 B 时 _1 已经被 drop
+        assert!(branch_from_b.is_dropped(l(2))); // 状态 B 时 _2 因为绑定关系也被 drop
+        assert!(branch_from_b.is_dropped(l(3))); // 状态 B 时 _3 因为绑定关系也被 drop
+        assert!(branch_from_b.states.contains_key(&l(3))); // 状态 B 时 _3 存在
         
         // 验证状态 A 和 B 没有被修改
-        assert_eq!(state_a.states.len(), 2); // 只有 _1 和 _2
-        assert_eq!(state_b.states.len(), 3); // _1, _2, _3
+        assert_eq!(state_a.states.keys().count(), 2); // 只有 _1 和 _2
+        assert_eq!(state_b.states.keys().count(), 3); // _1, _2, _3
     }
     
     /// 测试嵌套分支（分支中的分支）
@@ -447,47 +680,47 @@ mod tests {
         let mut manager = BindingManager::new("test_func");
         
         // 初始状态
-        manager.register("_1".to_string(), None);
-        manager.register("_2".to_string(), None);
-        manager.bind("_1", "_2").unwrap();
+        manager.register(l(1), None);
+        manager.register(l(2), None);
+        manager.bind(l(1), l(2)).unwrap();
         
         // 第一层分支：保存状态
         let level1_state = manager.clone();
         
         // 第一层分支 1
         let mut level1_branch1 = level1_state.clone();
-        level1_branch1.register("_3".to_string(), None);
-        level1_branch1.bind("_1", "_3").unwrap();
+        level1_branch1.register(l(3), None);
+        level1_branch1.bind(l(1), l(3)).unwrap();
         
         // 在第一层分支 1 中，进入第二层分支
         let level2_state = level1_branch1.clone();
         
         // 第二层分支 1
         let mut level2_branch1 = level2_state.clone();
-        level2_branch1.register("_4".to_string(), None);
-        level2_branch1.bind("_3", "_4").unwrap();
-        level2_branch1.idrop_group("_4");
+        level2_branch1.register(l(4), None);
+        level2_branch1.bind(l(3), l(4)).unwrap();
+        level2_branch1.idrop_group(l(4));
         
         // 第二层分支 2（从 level2_state 回溯）
         let mut level2_branch2 = level2_state.clone();
-        level2_branch2.register("_5".to_string(), None);
-        level2_branch2.bind("_3", "_5").unwrap();
+        level2_branch2.register(l(5), None);
+        level2_branch2.bind(l(3), l(5)).unwrap();
         // 注意：level2_branch2 没有 drop _5
         
         // 验证第二层分支 2 从 level2_state 回溯
-        assert!(!level2_branch2.is_dropped("_4")); // level2_state 时 _4 不存在
-        assert!(!level2_branch2.is_dropped("_5")); // level2_branch2 没有 drop _5
-        assert!(level2_branch2.states.contains_key("_3")); // level2_state 时 _3 存在
+        assert!(!level2_branch2.is_dropped(l(4))); // level2_state 时 _4 不存在
+        assert!(!level2_branch2.is_dropped(l(5))); // level2_branch2 没有 drop _5
+        assert!(level2_branch2.states.contains_key(&l(3))); // level2_state 时 _3 存在
         
         // 验证 level2_state 没有被修改
-        assert!(!level2_state.states.contains_key("_4"));
-        assert!(!level2_state.states.contains_key("_5"));
-        assert!(level2_state.states.contains_key("_3"));
+        assert!(!level2_state.states.contains_key(&l(4)));
+        assert!(!level2_state.states.contains_key(&l(5)));
+        assert!(level2_state.states.contains_key(&l(3)));
         
         // 验证第一层分支 1 的状态
-        assert!(level1_branch1.states.contains_key("_3"));
-        assert!(!level1_branch1.states.contains_key("_4")); // 第一层分支 1 没有直接注册 _4
-        assert!(!level1_branch1.states.contains_key("_5")); // 第一层分支 1 没有直接注册 _5
+        assert!(level1_branch1.states.contains_key(&l(3)));
+        assert!(!level1_branch1.states.contains_key(&l(4))); // 第一层分支 1 没有直接注册 _4
+        assert!(!level1_branch1.states.contains_key(&l(5))); // 第一层分支 1 没有直接注册 _5
     }
     
     /// 测试单分支（非分支路径）不保存状态
@@ -496,24 +729,24 @@ mod tests {
         let mut manager = BindingManager::new("test_func");
         
         // 初始状态
-        manager.register("_1".to_string(), None);
-        manager.register("_2".to_string(), None);
+        manager.register(l(1), None);
+        manager.register(l(2), None);
         
         // 单分支路径：连续操作，状态应该累积
-        manager.bind("_1", "_2").unwrap();
-        manager.register("_3".to_string(), None);
-        manager.bind("_1", "_3").unwrap();
-        manager.idrop_group("_1");
+        manager.bind(l(1), l(2)).unwrap();
+        manager.register(l(3), None);
+        manager.bind(l(1), l(3)).unwrap();
+        manager.idrop_group(l(1));
         
         // 验证状态累积
-        assert!(manager.is_dropped("_1"));
-        assert!(manager.is_dropped("_2")); // 因为绑定关系
-        assert!(manager.is_dropped("_3")); // 因为绑定关系
-        
-        let (_root, members) = manager.find_group("_1").unwrap();
-        assert!(members.contains(&"_1".to_string()));
-        assert!(members.contains(&"_2".to_string()));
-        assert!(members.contains(&"_3".to_string()));
+        assert!(manager.is_dropped(l(1)));
+        assert!(manager.is_dropped(l(2))); // 因为绑定关系
+        assert!(manager.is_dropped(l(3))); // 因为绑定关系
+        
+        let (_root, members) = manager.find_group(l(1)).unwrap();
+        assert!(members.contains(&l(1)));
+        assert!(members.contains(&l(2)));
+        assert!(members.contains(&l(3)));
     }
     
     /// 测试分支后状态合并（如果分支后汇合）
@@ -524,30 +757,30 @@ mod tests {
         let mut manager = BindingManager::new("test_func");
         
         // 初始状态
-        manager.register("_1".to_string(), None);
-        manager.register("_2".to_string(), None);
+        manager.register(l(1), None);
+        manager.register(l(2), None);
         
         // 保存状态
         let mut saved_state = manager.clone();
         
         // 分支 1：drop _1
         let mut branch1 = saved_state.clone();
-        branch1.idrop_group("_1");
+        branch1.idrop_group(l(1));
         
         // 分支 2：drop _2
         let mut branch2 = saved_state.clone();
-        branch2.idrop_group("_2");
+        branch2.idrop_group(l(2));
         
         // 验证分支独立性
-        assert!(branch1.is_dropped("_1"));
-        assert!(!branch1.is_dropped("_2")); // 分支 1 没有 drop _2
+        assert!(branch1.is_dropped(l(1)));
+        assert!(!branch1.is_dropped(l(2))); // 分支 1 没有 drop _2
         
-        assert!(!branch2.is_dropped("_1")); // 分支 2 没有 drop _1
-        assert!(branch2.is_dropped("_2"));
+        assert!(!branch2.is_dropped(l(1))); // 分支 2 没有 drop _1
+        assert!(branch2.is_dropped(l(2)));
         
         // 验证保存的状态没有被修改
-        assert!(!saved_state.is_dropped("_1"));
-        assert!(!saved_state.is_dropped("_2"));
+        assert!(!saved_state.is_dropped(l(1)));
+        assert!(!saved_state.is_dropped(l(2)));
     }
     
     // ========== k-predecessor 路径敏感性测试 ==========
@@ -560,6 +793,7 @@ mod tests {
         let config = DfsConfig {
             k_predecessor: 0,
             max_visits_per_block: 10,
+            ..Default::default()
         };
         
         let mut visit_state = VisitState::new(config);
@@ -584,6 +818,7 @@ mod tests {
         let config = DfsConfig {
             k_predecessor: 1,
             max_visits_per_block: 10,
+            ..Default::default()
         };
         
         let mut visit_state = VisitState::new(config);
@@ -617,6 +852,7 @@ mod tests {
         let config = DfsConfig {
             k_predecessor: 2,
             max_visits_per_block: 10,
+            ..Default::default()
         };
         
         let mut visit_state = VisitState::new(config);
@@ -659,6 +895,7 @@ mod tests {
         let config = DfsConfig {
             k_predecessor: 1,
             max_visits_per_block: 3,  // 最多访问 3 次
+            ..Default::default()
         };
         
         let mut visit_state = VisitState::new(config);
@@ -677,8 +914,58 @@ mod tests {
         let mut context = PathContext::new(1);
         context.push(BasicBlock::from_usize(99), 1);
         assert!(!visit_state.should_visit(bb1, &context), "Visit 4 should fail due to max_visits limit");
+        assert_eq!(visit_state.stats.outcome(), AnalysisOutcome::Truncated);
     }
-    
+
+    /// 测试步数预算用完后不再访问任何 block
+    #[test]
+    fn test_step_budget() {
+        use rustc_middle::mir::BasicBlock;
+
+        let config = DfsConfig {
+            k_predecessor: 0,
+            step_budget: Some(2),
+            ..Default::default()
+        };
+        let mut visit_state = VisitState::new(config.clone());
+        let context = PathContext::new(0);
+
+        assert!(visit_state.should_visit(BasicBlock::from_usize(0), &context));
+        assert_eq!(visit_state.stats.outcome(), AnalysisOutcome::Complete);
+        assert!(visit_state.should_visit(BasicBlock::from_usize(1), &context));
+        assert!(!visit_state.should_visit(BasicBlock::from_usize(2), &context));
+        assert!(!visit_state.should_visit(BasicBlock::from_usize(3), &context));
+
+        let stats = &visit_state.stats;
+        assert_eq!(stats.successful_visits, 2);
+        assert_eq!(stats.outcome(), AnalysisOutcome::Truncated);
+        let reason = stats.incomplete_reason(&config).unwrap();
+        assert!(reason.contains("step budget of 2"), "{}", reason);
+    }
+
+    /// 测试时间预算用完后结果标记为 timed out
+    #[test]
+    fn test_time_budget() {
+        use rustc_middle::mir::BasicBlock;
+
+        let config = DfsConfig {
+            time_budget_ms: Some(1),
+            ..Default::default()
+        };
+        let mut visit_state = VisitState::new(config.clone());
+        let context = PathContext::new(0);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        assert!(!visit_state.should_visit(BasicBlock::from_usize(0), &context));
+        assert_eq!(visit_state.stats.outcome(), AnalysisOutcome::TimedOut);
+        assert!(visit_state.stats.incomplete_reason(&config).unwrap().contains("1 ms"));
+
+        // 没有预算时遍历总是完整的
+        let stats = DfsStats::default();
+        assert_eq!(stats.outcome(), AnalysisOutcome::Complete);
+        assert!(stats.incomplete_reason(&DfsConfig::default()).is_none());
+    }
+
     /// 测试 PathContext 的 push 方法正确维护最近 k 个元素
     #[test]
     fn test_path_context_push() {
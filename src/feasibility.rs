@@ -0,0 +1,565 @@
+//! Path-feasibility pruning with `SwitchInt` constants.
+//! While a path is walked, a few facts about MIR locals are tracked: integer and bool constants,
+//! null / non-null raw pointers, the discriminant of enum values built on the path, and how a
+//! value was derived from another local (`_4 = Not(_3)`, `_3 = is_null(_1)`, `_5 = Eq(_2, 7)`).
+//! A `SwitchInt` on a known value only follows the matching target; every edge that is followed
+//! is recorded as a branch condition of the path and refines the switched value and the values it
+//! was derived from, so a later switch on the same condition follows the same edge.
+//!
+//! Locals whose address is taken (`&mut x`, `&raw x`, shared borrows of `!Freeze` values) can be
+//! changed behind the analysis' back and are never tracked.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rustc_middle::mir::{
+    AggregateKind, BasicBlock, BinOp, Body, CastKind, Local, Operand, Place, Rvalue, Statement,
+    StatementKind, SwitchTargets, Terminator, TerminatorKind, UnOp,
+};
+use rustc_middle::ty::adjustment::PointerCoercion;
+use rustc_middle::ty::{TyCtxt, TyKind};
+use rustc_span::{sym, Span};
+
+use crate::state::BindingManager;
+
+/// 当前路径上已知的值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnownValue {
+    /// 整数、bool（0/1）或 char
+    Int(u128),
+    /// 空指针
+    Null,
+    /// 非空指针（引用、`&raw` 取地址）
+    NonNull,
+    /// enum 值的判别值（`discriminant(x)` 的结果）
+    Discr(u128),
+}
+
+/// 值是怎样由另一个 local 得到的
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Derivation {
+    /// `_2 = copy _1`（包括指针之间的转换）
+    Copy,
+    /// `_2 = Not(_1)`（bool）
+    Not,
+    /// `_2 = is_null(_1)` 或 `_2 = Eq(_1, null)`；为 false 时表示 `_2 = Ne(_1, null)`
+    IsNull(bool),
+    /// `_2 = Eq(_1, c)`
+    EqConst(u128),
+    /// `_2 = Ne(_1, c)`
+    NeConst(u128),
+    /// `_2 = discriminant(_1)`
+    Discriminant,
+}
+
+/// 值的来源；只在来源没有被重新赋值（`stamp` 不变）时有效
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Origin {
+    kind: Derivation,
+    source: Local,
+    stamp: Option<u64>,
+}
+
+/// 一个 local 在当前路径上的值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueFact {
+    pub value: Option<KnownValue>,
+    origin: Option<Origin>,
+    /// 最近一次赋值的编号（函数入口处为 None）
+    stamp: Option<u64>,
+}
+
+/// 赋值编号；全局递增，不同路径上的赋值不会得到相同的编号
+static NEXT_STAMP: AtomicU64 = AtomicU64::new(0);
+
+impl ValueFact {
+    /// 函数入口处的未知值
+    fn entry() -> Self {
+        Self { value: None, origin: None, stamp: None }
+    }
+
+    /// 一次新的赋值
+    fn assigned(value: Option<KnownValue>, origin: Option<Origin>) -> Self {
+        let stamp = NEXT_STAMP.fetch_add(1, Ordering::Relaxed);
+        Self { value, origin, stamp: Some(stamp) }
+    }
+
+    /// 汇合点的值：两边相同则不变，否则只保留相同的值、丢弃来源；返回 self 需要变成的值
+    pub fn join(&self, other: Option<&ValueFact>) -> Option<ValueFact> {
+        if other == Some(self) {
+            return None;
+        }
+        let value = other.and_then(|other| other.value).filter(|value| self.value == Some(*value));
+        if self.origin.is_none() && self.value == value {
+            return None;
+        }
+        Some(Self::assigned(value, None))
+    }
+}
+
+/// `SwitchInt` 的一条出边
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SwitchEdge {
+    /// 值等于某个分支值
+    Value(u128),
+    /// otherwise：值不等于所有分支值
+    Otherwise(Vec<u128>),
+}
+
+/// 路径上假设成立的分支条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchCondition {
+    /// SwitchInt 所在的 BasicBlock
+    pub bb: BasicBlock,
+    pub span: Span,
+    /// 被判断的值，如 `_4`、`discriminant(_1)`
+    pub subject: String,
+    pub is_bool: bool,
+    pub edge: SwitchEdge,
+    /// 被判断的 local（常量为 None）
+    local: Option<Local>,
+}
+
+impl BranchCondition {
+    /// 条件的文字描述，如 "`_4` is false"、"`discriminant(_1)` == 1"
+    pub fn describe(&self) -> String {
+        match (&self.edge, self.is_bool) {
+            (SwitchEdge::Value(0), true) => format!("`{}` is false", self.subject),
+            (SwitchEdge::Value(_), true) | (SwitchEdge::Otherwise(_), true) => {
+                format!("`{}` is true", self.subject)
+            }
+            (SwitchEdge::Value(value), false) => format!("`{}` == {}", self.subject, value),
+            (SwitchEdge::Otherwise(excluded), false) => match excluded.as_slice() {
+                [value] => format!("`{}` != {}", self.subject, value),
+                values => {
+                    let values: Vec<String> = values.iter().map(u128::to_string).collect();
+                    format!("`{}` not in {{{}}}", self.subject, values.join(", "))
+                }
+            },
+        }
+    }
+}
+
+/// 函数入口：为所有可以追踪的 local 记录未知值（取了可变地址的 local 不追踪）
+pub fn seed<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, manager: &mut BindingManager) {
+    let typing_env = body.typing_env(tcx);
+    let mut untracked = vec![false; body.local_decls.len()];
+    for block in body.basic_blocks.iter() {
+        for stmt in &block.statements {
+            let StatementKind::Assign(box (_, rvalue)) = &stmt.kind else { continue };
+            let borrowed = match rvalue {
+                Rvalue::Ref(_, kind, place) => {
+                    let ty = body.local_decls[place.local].ty;
+                    (kind.mutability().is_mut() || !ty.is_freeze(tcx, typing_env)).then_some(place)
+                }
+                Rvalue::RawPtr(_, place) => Some(place),
+                _ => None,
+            };
+            if let Some(place) = borrowed.filter(|place| !place.is_indirect()) {
+                untracked[place.local.as_usize()] = true;
+            }
+        }
+    }
+    for local in body.local_decls.indices() {
+        if !untracked[local.as_usize()] {
+            manager.set_value_fact(local, ValueFact::entry());
+        }
+    }
+}
+
+/// 按语句更新已知值（在检测之后调用）
+pub fn track_statement<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, stmt: &Statement<'tcx>, manager: &mut BindingManager) {
+    match &stmt.kind {
+        StatementKind::Assign(box (place, rvalue)) => {
+            if let Some(local) = place.as_local() {
+                let (value, origin) = eval_rvalue(tcx, body, rvalue, manager);
+                assign(manager, local, value, origin);
+            }
+        }
+        StatementKind::SetDiscriminant { place, variant_index } => {
+            if let Some(local) = place.as_local() {
+                let discr = body.local_decls[local].ty.discriminant_for_variant(tcx, *variant_index);
+                assign(manager, local, discr.map(|discr| KnownValue::Discr(discr.val)), None);
+            }
+        }
+        StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+            assign(manager, *local, None, None);
+        }
+        StatementKind::Deinit(place) => {
+            if let Some(local) = place.as_local() {
+                assign(manager, local, None, None);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 按 terminator 更新已知值：调用的返回值（`null()`、`is_null()` 已知，其余未知）
+pub fn track_terminator<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, term: &Terminator<'tcx>, manager: &mut BindingManager) {
+    let TerminatorKind::Call { func, args, destination, .. } = &term.kind else {
+        return;
+    };
+    let Some(local) = destination.as_local() else {
+        return;
+    };
+    let (mut value, mut origin) = (None, None);
+    if let TyKind::FnDef(def_id, _) = func.ty(body, tcx).kind() {
+        let is_item = |name| tcx.is_diagnostic_item(name, *def_id);
+        if is_item(sym::ptr_null) || is_item(sym::ptr_null_mut) {
+            value = Some(KnownValue::Null);
+        } else if (is_item(sym::ptr_is_null) || is_item(sym::ptr_const_is_null)) && !args.is_empty() {
+            let arg = &args[0].node;
+            value = operand_value(arg, manager).and_then(|value| is_null(value, true));
+            origin = derived(Derivation::IsNull(true), arg, manager);
+        }
+    }
+    assign(manager, local, value, origin);
+}
+
+/// 当前状态下可能走的后继及对应的分支条件（只有 SwitchInt 的出边带条件）
+pub fn feasible_successors(
+    body: &Body<'_>,
+    bb: BasicBlock,
+    term: &Terminator<'_>,
+    manager: &BindingManager,
+) -> Vec<(BasicBlock, Option<BranchCondition>)> {
+    let TerminatorKind::SwitchInt { discr, targets } = &term.kind else {
+        return term.successors().map(|succ| (succ, None)).collect();
+    };
+    let known = match operand_value(discr, manager) {
+        Some(KnownValue::Int(value)) => Some(value),
+        _ => None,
+    };
+    let local = discr.place().and_then(|place| place.as_local());
+    let is_bool = match discr {
+        Operand::Constant(constant) => constant.ty().is_bool(),
+        Operand::Copy(place) | Operand::Move(place) => {
+            place.as_local().is_some_and(|local| body.local_decls[local].ty.is_bool())
+        }
+    };
+    let subject = subject(discr, manager);
+    switch_edges(targets, known)
+        .into_iter()
+        .map(|(succ, edge)| {
+            let condition = BranchCondition {
+                bb,
+                span: term.source_info.span,
+                subject: subject.clone(),
+                is_bool,
+                edge,
+                local,
+            };
+            (succ, Some(condition))
+        })
+        .collect()
+}
+
+/// 走 SwitchInt 的一条出边：记录分支条件，并据此细化被判断的值
+pub fn assume(manager: &mut BindingManager, condition: BranchCondition) {
+    if let Some(local) = condition.local {
+        match &condition.edge {
+            SwitchEdge::Value(value) => refine(manager, local, KnownValue::Int(*value)),
+            // bool 的 otherwise 分支只能是 true
+            SwitchEdge::Otherwise(_) if condition.is_bool => refine(manager, local, KnownValue::Int(1)),
+            SwitchEdge::Otherwise(_) => {}
+        }
+    }
+    manager.push_condition(condition);
+}
+
+/// SwitchInt 的出边；值已知时只保留匹配的一条
+fn switch_edges(targets: &SwitchTargets, known: Option<u128>) -> Vec<(BasicBlock, SwitchEdge)> {
+    let values: Vec<u128> = targets.iter().map(|(value, _)| value).collect();
+    let otherwise = (targets.otherwise(), SwitchEdge::Otherwise(values));
+    match known {
+        Some(value) => match targets.iter().find(|(candidate, _)| *candidate == value) {
+            Some((_, target)) => vec![(target, SwitchEdge::Value(value))],
+            None => vec![otherwise],
+        },
+        None => {
+            let mut edges: Vec<_> = targets.iter().map(|(value, target)| (target, SwitchEdge::Value(value))).collect();
+            edges.push(otherwise);
+            edges
+        }
+    }
+}
+
+/// 给 local 赋新值（不追踪的 local 忽略）
+fn assign(manager: &mut BindingManager, local: Local, value: Option<KnownValue>, origin: Option<Origin>) {
+    if manager.value_fact(local).is_some() {
+        manager.set_value_fact(local, ValueFact::assigned(value, origin));
+    }
+}
+
+/// 分支条件成立时 local 的值为 `value`；沿来源链反推
+fn refine(manager: &mut BindingManager, local: Local, value: KnownValue) {
+    let Some(fact) = manager.value_fact(local).copied() else {
+        return;
+    };
+    manager.set_value_fact(local, ValueFact { value: Some(value), ..fact });
+    let Some(origin) = fact.origin else {
+        return;
+    };
+    if manager.value_fact(origin.source).map(|source| source.stamp) != Some(origin.stamp) {
+        return;
+    }
+    let source_value = match (origin.kind, value) {
+        (Derivation::Copy, value) => Some(value),
+        (Derivation::Not, KnownValue::Int(bit)) => Some(KnownValue::Int(bit ^ 1)),
+        (Derivation::IsNull(expected), KnownValue::Int(bit)) => {
+            Some(if (bit == 1) == expected { KnownValue::Null } else { KnownValue::NonNull })
+        }
+        (Derivation::EqConst(constant), KnownValue::Int(1)) | (Derivation::NeConst(constant), KnownValue::Int(0)) => {
+            Some(KnownValue::Int(constant))
+        }
+        (Derivation::Discriminant, KnownValue::Int(discr)) => Some(KnownValue::Discr(discr)),
+        _ => None,
+    };
+    if let Some(source_value) = source_value {
+        refine(manager, origin.source, source_value);
+    }
+}
+
+/// 计算赋值右边的值及其来源
+fn eval_rvalue<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    rvalue: &Rvalue<'tcx>,
+    manager: &BindingManager,
+) -> (Option<KnownValue>, Option<Origin>) {
+    match rvalue {
+        Rvalue::Use(operand) => (operand_value(operand, manager), derived(Derivation::Copy, operand, manager)),
+        Rvalue::Cast(CastKind::PtrToPtr | CastKind::PointerCoercion(PointerCoercion::MutToConstPointer, _), operand, _) => {
+            (operand_value(operand, manager), derived(Derivation::Copy, operand, manager))
+        }
+        Rvalue::Ref(..) | Rvalue::RawPtr(..) => (Some(KnownValue::NonNull), None),
+        Rvalue::UnaryOp(UnOp::Not, operand) if operand.ty(body, tcx).is_bool() => {
+            let value = match operand_value(operand, manager) {
+                Some(KnownValue::Int(bit)) => Some(KnownValue::Int(bit ^ 1)),
+                _ => None,
+            };
+            (value, derived(Derivation::Not, operand, manager))
+        }
+        Rvalue::BinaryOp(op @ (BinOp::Eq | BinOp::Ne), box (lhs, rhs)) => {
+            let equal = match (operand_value(lhs, manager), operand_value(rhs, manager)) {
+                (Some(KnownValue::Int(a)), Some(KnownValue::Int(b))) => Some(a == b),
+                (Some(KnownValue::Null), Some(KnownValue::Null)) => Some(true),
+                (Some(KnownValue::Null), Some(KnownValue::NonNull))
+                | (Some(KnownValue::NonNull), Some(KnownValue::Null)) => Some(false),
+                _ => None,
+            };
+            let is_eq = *op == BinOp::Eq;
+            let value = equal.map(|equal| KnownValue::Int((equal == is_eq) as u128));
+            // 与常量比较：`x == c`、`p != null`
+            let origin = [(lhs, rhs), (rhs, lhs)].into_iter().find_map(|(var, constant)| {
+                let Operand::Constant(_) = constant else { return None };
+                let kind = match (operand_value(constant, manager)?, is_eq) {
+                    (KnownValue::Int(c), true) => Derivation::EqConst(c),
+                    (KnownValue::Int(c), false) => Derivation::NeConst(c),
+                    (KnownValue::Null, is_eq) => Derivation::IsNull(is_eq),
+                    _ => return None,
+                };
+                derived(kind, var, manager)
+            });
+            (value, origin)
+        }
+        Rvalue::Discriminant(place) => {
+            let value = match place_value(place, manager) {
+                Some(KnownValue::Discr(discr)) => Some(KnownValue::Int(discr)),
+                _ => None,
+            };
+            let origin = place.as_local().and_then(|local| origin_of(Derivation::Discriminant, local, manager));
+            (value, origin)
+        }
+        Rvalue::Aggregate(box AggregateKind::Adt(def_id, variant, ..), _) => {
+            let adt = tcx.adt_def(*def_id);
+            let value = adt.is_enum().then(|| KnownValue::Discr(adt.discriminant_for_variant(tcx, *variant).val));
+            (value, None)
+        }
+        _ => (None, None),
+    }
+}
+
+/// 操作数的已知值（常量或已追踪的 local）
+fn operand_value(operand: &Operand<'_>, manager: &BindingManager) -> Option<KnownValue> {
+    match operand {
+        Operand::Constant(constant) => {
+            let bits = constant.const_.try_to_scalar_int()?.to_bits_unchecked();
+            if constant.ty().is_raw_ptr() {
+                Some(if bits == 0 { KnownValue::Null } else { KnownValue::NonNull })
+            } else {
+                Some(KnownValue::Int(bits))
+            }
+        }
+        Operand::Copy(place) | Operand::Move(place) => place_value(place, manager),
+    }
+}
+
+fn place_value(place: &Place<'_>, manager: &BindingManager) -> Option<KnownValue> {
+    manager.value_fact(place.as_local()?)?.value
+}
+
+/// `is_null` 的结果；`expected` 为 false 时是 `!= null`
+fn is_null(value: KnownValue, expected: bool) -> Option<KnownValue> {
+    let null = match value {
+        KnownValue::Null | KnownValue::Int(0) => true,
+        KnownValue::NonNull | KnownValue::Int(_) => false,
+        KnownValue::Discr(_) => return None,
+    };
+    Some(KnownValue::Int((null == expected) as u128))
+}
+
+/// 由操作数得到的值的来源（只有已追踪的 local 有来源）
+fn derived(kind: Derivation, operand: &Operand<'_>, manager: &BindingManager) -> Option<Origin> {
+    let local = operand.place()?.as_local()?;
+    origin_of(kind, local, manager)
+}
+
+fn origin_of(kind: Derivation, source: Local, manager: &BindingManager) -> Option<Origin> {
+    let fact = manager.value_fact(source)?;
+    Some(Origin { kind, source, stamp: fact.stamp })
+}
+
+/// 分支条件中被判断的值：由 `discriminant(x)` 得到的值显示为 `discriminant(x)`
+fn subject(discr: &Operand<'_>, manager: &BindingManager) -> String {
+    let Some(local) = discr.place().and_then(|place| place.as_local()) else {
+        return format!("{:?}", discr);
+    };
+    let origin = manager.value_fact(local).and_then(|fact| fact.origin);
+    match origin {
+        Some(Origin { kind: Derivation::Discriminant, source, stamp })
+            if manager.value_fact(source).map(|fact| fact.stamp) == Some(stamp) =>
+        {
+            format!("discriminant({:?})", source)
+        }
+        _ => format!("{:?}", local),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::TraversalEngine;
+
+    fn local(idx: usize) -> Local {
+        Local::from_usize(idx)
+    }
+
+    fn condition(local_idx: usize, is_bool: bool, edge: SwitchEdge) -> BranchCondition {
+        BranchCondition {
+            bb: BasicBlock::from_u32(0),
+            span: rustc_span::DUMMY_SP,
+            subject: format!("_{}", local_idx),
+            is_bool,
+            edge,
+            local: Some(local(local_idx)),
+        }
+    }
+
+    /// 测试1: 已知值只走匹配的出边，未知值走所有出边
+    #[test]
+    fn test_switch_edges() {
+        let bb = BasicBlock::from_u32;
+        let targets = SwitchTargets::new([(0, bb(1)), (2, bb(2))].into_iter(), bb(3));
+
+        let all = switch_edges(&targets, None);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[2], (bb(3), SwitchEdge::Otherwise(vec![0, 2])));
+
+        assert_eq!(switch_edges(&targets, Some(2)), vec![(bb(2), SwitchEdge::Value(2))]);
+        assert_eq!(switch_edges(&targets, Some(7)), vec![(bb(3), SwitchEdge::Otherwise(vec![0, 2]))]);
+    }
+
+    /// 测试2: 分支条件沿来源链反推：`_3 = is_null(_1)`、`_4 = Not(_3)`，`_4` 为 false 则 `_1` 为空
+    #[test]
+    fn test_assume_refines_origins() {
+        let mut manager = BindingManager::new("f");
+        for idx in 1..=4 {
+            manager.set_value_fact(local(idx), ValueFact::entry());
+        }
+        let is_null = origin_of(Derivation::IsNull(true), local(1), &manager);
+        assign(&mut manager, local(3), None, is_null);
+        let not = origin_of(Derivation::Not, local(3), &manager);
+        assign(&mut manager, local(4), None, not);
+
+        let checkpoint = manager.checkpoint();
+        assume(&mut manager, condition(4, true, SwitchEdge::Value(0)));
+        assert_eq!(manager.value_fact(local(3)).unwrap().value, Some(KnownValue::Int(1)));
+        assert_eq!(manager.value_fact(local(1)).unwrap().value, Some(KnownValue::Null));
+        assert_eq!(manager.conditions().len(), 1);
+        assert_eq!(manager.conditions()[0].describe(), "`_4` is false");
+
+        // 另一条出边从分支前的状态开始
+        manager.rollback(&checkpoint);
+        assert_eq!(manager.value_fact(local(1)).unwrap().value, None);
+        assert!(manager.conditions().is_empty());
+        assume(&mut manager, condition(4, true, SwitchEdge::Otherwise(vec![0])));
+        assert_eq!(manager.value_fact(local(1)).unwrap().value, Some(KnownValue::NonNull));
+        manager.release(checkpoint);
+
+        // 来源被重新赋值后不再反推
+        assign(&mut manager, local(1), None, None);
+        assume(&mut manager, condition(4, true, SwitchEdge::Value(0)));
+        assert_eq!(manager.value_fact(local(1)).unwrap().value, None);
+    }
+
+    /// 测试3: 不追踪的 local 不记录值；汇合点只保留两边相同的值
+    #[test]
+    fn test_untracked_and_join() {
+        let mut manager = BindingManager::new("f");
+        manager.set_value_fact(local(1), ValueFact::entry());
+        assign(&mut manager, local(2), Some(KnownValue::Int(1)), None);
+        assert!(manager.value_fact(local(2)).is_none());
+
+        let mut left = manager.clone();
+        let mut right = manager.clone();
+        assign(&mut left, local(1), Some(KnownValue::Int(1)), None);
+        assign(&mut right, local(1), Some(KnownValue::Int(1)), None);
+        assert!(!left.join(&right));
+        assert_eq!(left.value_fact(local(1)).unwrap().value, Some(KnownValue::Int(1)));
+
+        assign(&mut right, local(1), Some(KnownValue::Int(0)), None);
+        assert!(left.join(&right));
+        assert_eq!(left.value_fact(local(1)).unwrap().value, None);
+        assert!(!left.join(&right));
+    }
+
+    /// 测试4: 分支条件的文字描述
+    #[test]
+    fn test_describe() {
+        assert_eq!(condition(4, true, SwitchEdge::Otherwise(vec![0])).describe(), "`_4` is true");
+        assert_eq!(condition(5, false, SwitchEdge::Value(3)).describe(), "`_5` == 3");
+        assert_eq!(condition(5, false, SwitchEdge::Otherwise(vec![1])).describe(), "`_5` != 1");
+        assert_eq!(condition(5, false, SwitchEdge::Otherwise(vec![0, 1])).describe(), "`_5` not in {0, 1}");
+    }
+
+    /// 测试5: worklist 引擎中 `if c { drop(v) } ... if c { use(v) }`：两条出边上相反的条件在汇合点
+    /// 被去掉，结果不附带条件；DFS 引擎报告同一条路径上的条件
+    #[test]
+    fn test_join_conditions() {
+        let manager = BindingManager::new("f");
+        let mut dropped = manager.clone();
+        let mut kept = manager.clone();
+        assume(&mut dropped, condition(1, true, SwitchEdge::Otherwise(vec![0])));
+        assume(&mut kept, condition(1, true, SwitchEdge::Value(0)));
+        dropped.join(&kept);
+        assert!(dropped.conditions().is_empty());
+
+        let mut shared = manager.clone();
+        assume(&mut shared, condition(2, false, SwitchEdge::Value(3)));
+        let mut other = shared.clone();
+        assume(&mut other, condition(1, true, SwitchEdge::Value(0)));
+        shared.join(&other);
+        assert_eq!(shared.conditions(), &[condition(2, false, SwitchEdge::Value(3))]);
+
+        // 第二个 `if c` 的 true 出边
+        assume(&mut dropped, condition(1, true, SwitchEdge::Otherwise(vec![0])));
+        let describe = |engine| -> Vec<String> {
+            crate::finding::condition_steps(dropped.conditions(), engine)
+                .into_iter()
+                .map(|step| step.message)
+                .collect()
+        };
+        assert!(describe(TraversalEngine::Worklist).is_empty());
+        assert_eq!(describe(TraversalEngine::Dfs), vec!["`_1` is true"]);
+    }
+}
@@ -0,0 +1,532 @@
+//! Findings collected during the analysis.
+//! The detectors record a `Finding` for every error they report in the `FindingCollector` of the
+//! function being analyzed, which is passed down explicitly so functions analyzed on different
+//! threads never share one. The DFS reaches the same error once per path, so findings are merged
+//! on (function, detector, use span, drop span) and printed when the function is done. Findings
+//! keep raw `Span`s (valid for the whole compiler session) and are resolved to file/line/column
+//! only when the machine-readable reports are written at the end of `analyze_crate`.
+
+use std::collections::{HashMap, HashSet};
+
+use rustc_middle::mir::{BasicBlock, Body, Local};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+use serde::Serialize;
+
+use crate::feasibility::BranchCondition;
+use crate::options::{Detector, TraversalEngine};
+use crate::place::PlaceId;
+use crate::state::{BindingManager, DropInfo, DropLocation, LocalState, PathEvent, PathEventKind};
+
+/// 检测到的错误类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FindingKind {
+    /// drop 之后的使用
+    UseAfterDrop,
+    /// 解引用指向的值已经被 drop 的裸指针
+    DanglingPointer,
+    /// 通过指针使用已经离开作用域（StorageDead）的栈内存，或者指向它的指针逃逸到静态内存
+    UseAfterScope,
+    /// 返回值（或它的字段）指向在返回前被 drop 或离开作用域的 local
+    DanglingReturn,
+    /// 裸指针通过 `from_raw` 重新取得的内存仍然被另一个值拥有
+    DoubleOwnership,
+    /// 同一块内存的两个不同所有者（from_raw 取得所有权的一方和原来的所有者）都释放了它
+    DoubleFree,
+    /// 正常路径（不是 panic 时的 unwind 路径）上再次 drop 已经被 drop 的值
+    DoubleDrop,
+    /// 持有所有权的内存既没有释放也没有交出去（into_raw / `ManuallyDrop::new` 之后没有取回，
+    /// 或者被 `ptr::write` 覆盖）
+    Leak,
+    /// 污点数据到达 sink
+    TaintedSink,
+}
+
+impl FindingKind {
+    pub const ALL: &'static [FindingKind] =
+        &[
+            FindingKind::UseAfterDrop,
+            FindingKind::DanglingPointer,
+            FindingKind::UseAfterScope,
+            FindingKind::DanglingReturn,
+            FindingKind::DoubleOwnership,
+            FindingKind::DoubleFree,
+            FindingKind::DoubleDrop,
+            FindingKind::Leak,
+            FindingKind::TaintedSink,
+        ];
+
+    /// 产生该结果的检测器
+    pub fn detector(self) -> Detector {
+        match self {
+            FindingKind::UseAfterDrop
+            | FindingKind::DanglingPointer
+            | FindingKind::UseAfterScope
+            | FindingKind::DanglingReturn
+            | FindingKind::DoubleOwnership
+            | FindingKind::DoubleFree
+            | FindingKind::DoubleDrop => Detector::UseAfterDrop,
+            FindingKind::Leak => Detector::Leak,
+            FindingKind::TaintedSink => Detector::Taint,
+        }
+    }
+
+    /// 规则 ID（SARIF `ruleId`）
+    pub fn rule_id(self) -> &'static str {
+        match self {
+            FindingKind::UseAfterDrop => "use-after-drop",
+            FindingKind::DanglingPointer => "dangling-pointer",
+            FindingKind::UseAfterScope => "use-after-scope",
+            FindingKind::DanglingReturn => "dangling-return",
+            FindingKind::DoubleOwnership => "double-ownership",
+            FindingKind::DoubleFree => "double-free",
+            FindingKind::DoubleDrop => "double-drop",
+            FindingKind::Leak => "memory-leak",
+            FindingKind::TaintedSink => "tainted-sink",
+        }
+    }
+
+    /// 规则的简短说明
+    pub fn description(self) -> &'static str {
+        match self {
+            FindingKind::UseAfterDrop => "A value is used after it has been dropped",
+            FindingKind::DanglingPointer => "A raw pointer is dereferenced after its pointee was dropped",
+            FindingKind::UseAfterScope => "Stack memory is used through a pointer after its scope ended, or a pointer to it escapes",
+            FindingKind::DanglingReturn => "A returned pointer points to a local that is dropped or goes out of scope before returning",
+            FindingKind::DoubleOwnership => "Memory still owned by a value is reclaimed from a raw pointer, so it will be freed twice",
+            FindingKind::DoubleFree => "Two distinct owners of the same allocation both free it",
+            FindingKind::DoubleDrop => "A value is dropped again on a normal (non-unwind) path after it was already dropped",
+            FindingKind::Leak => "Owned memory is never freed: its ownership was released and not reclaimed, or it was overwritten without being dropped",
+            FindingKind::TaintedSink => "Data from an untrusted source reaches a sensitive sink",
+        }
+    }
+}
+
+/// 路径上的一个 BasicBlock 及其 terminator 的位置
+#[derive(Debug, Clone, Copy)]
+pub struct PathStep {
+    pub bb: BasicBlock,
+    pub span: Span,
+}
+
+/// 出错的 MIR 语句或 terminator（Debug 文本）
+#[derive(Debug, Clone)]
+pub enum MirSite {
+    Statement(String),
+    Terminator(String),
+}
+
+impl MirSite {
+    pub fn text(&self) -> &str {
+        match self {
+            MirSite::Statement(text) | MirSite::Terminator(text) => text,
+        }
+    }
+}
+
+/// 错误步骤说明中的一步，如 "moved into `_5` here"
+#[derive(Debug, Clone)]
+pub struct TraceStep {
+    /// 这一步所在的 BasicBlock
+    pub bb: BasicBlock,
+    pub span: Span,
+    pub message: String,
+}
+
+impl TraceStep {
+    fn from_event(event: &PathEvent) -> Self {
+        let from = event.from.map_or_else(|| "?".to_string(), |from| from.to_string());
+        let message = match event.kind {
+            PathEventKind::Bind => format!("`{}` aliased by `{}` here", from, event.local),
+            PathEventKind::Move => format!("moved into `{}` here", event.local),
+            PathEventKind::Drop => "dropped here".to_string(),
+            PathEventKind::Reassign => format!("`{}` reassigned here", event.local),
+            PathEventKind::StorageDead => "goes out of scope here".to_string(),
+            PathEventKind::EscapeToGlobal => format!("`{}` stored into static memory here", event.local),
+            PathEventKind::IntoRaw => format!("ownership of `{}` released into `{}` here", from, event.local),
+            PathEventKind::FromRaw => format!("ownership reclaimed from `{}` by `{}` here", from, event.local),
+        };
+        Self { bb: event.bb, span: event.span, message }
+    }
+}
+
+/// 污点从哪里来、到达哪个 sink
+#[derive(Debug, Clone)]
+pub struct TaintFlow {
+    pub source: String,
+    pub sink: String,
+    pub param: usize,
+}
+
+/// 一次检测结果
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub kind: FindingKind,
+    /// 所在函数
+    pub function: String,
+    /// 出错的变量（如 `_3`）
+    pub local: String,
+    /// 变量所属的 MIR local（`_3.1`、`*_3` 都属于 `_3`）
+    pub base: Local,
+    /// 变量所属 local 的类型
+    pub local_ty: Option<String>,
+    /// 一句话描述（英文，用于机器可读输出）
+    pub message: String,
+    /// 出错的 MIR 语句或 terminator
+    pub mir: MirSite,
+    /// 使用位置
+    pub span: Span,
+    pub bb: BasicBlock,
+    /// use-after-drop 的 drop 位置
+    pub drop: Option<DropInfo>,
+    /// 报告时变量是否已被 drop（也可能是它依赖的变量被 drop）
+    pub is_dropped: bool,
+    /// 污点检测的 source/sink
+    pub taint: Option<TaintFlow>,
+    /// 变量所在绑定组的根
+    pub group_root: Option<String>,
+    /// 绑定组成员（排序后）
+    pub group_members: Vec<String>,
+    /// 从函数入口到使用位置的 DFS 路径（第一次到达时的路径）
+    pub path: Vec<PathStep>,
+    /// 到达该错误的不同路径数
+    pub path_count: usize,
+    /// 当前路径上与该变量所在组相关的事件，最后一步是使用本身
+    pub steps: Vec<TraceStep>,
+    /// 到达使用位置时假设成立的分支条件，如 "`_4` is false"（只有 DFS 引擎逐条路径报告时才有）
+    pub conditions: Vec<TraceStep>,
+    /// 出错位置在 panic 时的 unwind 路径上（cleanup block）
+    pub unwind: bool,
+}
+
+impl Finding {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        kind: FindingKind,
+        function: &str,
+        local: PlaceId,
+        message: String,
+        mir: MirSite,
+        span: Span,
+        bb: BasicBlock,
+        body: &Body,
+        manager: &mut BindingManager,
+    ) -> Self {
+        let path = manager
+            .path()
+            .iter()
+            .map(|&bb| PathStep { bb, span: body.basic_blocks[bb].terminator().source_info.span })
+            .collect();
+        let (group_root, group_members) = match manager.find_group(local) {
+            Some((root, members)) => (Some(root), members),
+            None => (None, Vec::new()),
+        };
+        let steps = trace_steps(manager.events(), &group_members, local, bb, span);
+        let conditions = condition_steps(manager.conditions(), crate::config::get().dfs.engine);
+        let drop = match (kind, group_root) {
+            (FindingKind::TaintedSink | FindingKind::DoubleOwnership | FindingKind::Leak, _) | (_, None) => None,
+            (_, Some(root)) => LocalState::get_drop_info(root, &manager.states),
+        };
+        let is_dropped = manager.is_dropped(local);
+        let base = Local::from_usize(local.local_index());
+        let local_ty = body.local_decls.get(base).map(|decl| format!("{:?}", decl.ty));
+        let mut group_members: Vec<String> = group_members.iter().map(PlaceId::to_string).collect();
+        group_members.sort();
+        Self {
+            kind,
+            function: function.to_string(),
+            local: local.to_string(),
+            base,
+            local_ty,
+            message,
+            mir,
+            span,
+            bb,
+            drop,
+            is_dropped,
+            taint: None,
+            group_root: group_root.map(|root| root.to_string()),
+            group_members,
+            path,
+            path_count: 1,
+            steps,
+            conditions,
+            unwind: body.basic_blocks[bb].is_cleanup,
+        }
+    }
+
+    /// 一行的步骤说明，如 "moved into `_5` here → dropped here → dereferenced here"
+    pub fn trace(&self) -> String {
+        self.steps.iter().map(|step| step.message.as_str()).collect::<Vec<_>>().join(" → ")
+    }
+
+    /// 合并重复结果使用的键
+    fn key(&self) -> FindingKey {
+        (self.function.clone(), self.kind.detector(), self.span, self.drop_span())
+    }
+
+    /// drop 位置的说明（StorageDead 为离开作用域）
+    pub fn drop_label(&self) -> &'static str {
+        match self.drop.as_ref().map(|info| &info.location) {
+            _ if self.kind == FindingKind::DoubleFree => "memory first freed here",
+            Some(DropLocation::Statement { .. }) => "value goes out of scope here",
+            _ => "value dropped here",
+        }
+    }
+
+    /// drop 位置的源码 Span
+    pub fn drop_span(&self) -> Option<Span> {
+        self.drop.as_ref().map(|info| match &info.location {
+            DropLocation::Terminator { span, .. } | DropLocation::Statement { span, .. } => *span,
+        })
+    }
+}
+
+/// 从路径事件中挑出涉及 `members`（出错变量所在的绑定组）的事件，并加上使用这一步
+fn trace_steps(
+    events: &[PathEvent],
+    members: &[PlaceId],
+    local: PlaceId,
+    use_bb: BasicBlock,
+    use_span: Span,
+) -> Vec<TraceStep> {
+    let is_member = |id: PlaceId| members.contains(&id);
+    let mut steps: Vec<TraceStep> = events
+        .iter()
+        .filter(|event| is_member(event.local) || event.from.is_some_and(is_member))
+        .map(TraceStep::from_event)
+        .collect();
+    let use_message = if local.is_deref() {
+        "dereferenced here"
+    } else {
+        "used here"
+    };
+    steps.push(TraceStep { bb: use_bb, span: use_span, message: use_message.to_string() });
+    steps
+}
+
+/// 路径上的分支条件（循环中重复走的同一条出边只保留一次）
+///
+/// worklist 引擎的状态在汇合点由多条路径 join 而来，不对应某一条具体路径，因此不附带条件。
+pub(crate) fn condition_steps(conditions: &[BranchCondition], engine: TraversalEngine) -> Vec<TraceStep> {
+    if engine != TraversalEngine::Dfs {
+        return Vec::new();
+    }
+    let mut seen = HashSet::new();
+    conditions
+        .iter()
+        .filter(|condition| seen.insert((condition.bb, condition.edge.clone())))
+        .map(|condition| TraceStep { bb: condition.bb, span: condition.span, message: condition.describe() })
+        .collect()
+}
+
+/// (函数, 检测器, 使用位置, drop 位置)；同一组嵌套的使用位置另外合并（见 `nested_duplicate`）
+type FindingKey = (String, Detector, Span, Option<Span>);
+
+/// 合并同一个错误在不同 DFS 路径上的重复报告
+#[derive(Debug, Default)]
+pub struct FindingCollector {
+    findings: Vec<Finding>,
+    index: HashMap<FindingKey, usize>,
+    /// 每个结果已经见过的路径
+    paths: Vec<HashSet<Vec<BasicBlock>>>,
+}
+
+impl FindingCollector {
+    /// 加入一个结果；返回是否是新的错误（而不是已有错误的另一条路径）
+    pub fn add(&mut self, finding: Finding) -> bool {
+        let path: Vec<BasicBlock> = finding.path.iter().map(|step| step.bb).collect();
+        match self.index.get(&finding.key()).copied().or_else(|| self.nested_duplicate(&finding)) {
+            Some(idx) => {
+                self.index.insert(finding.key(), idx);
+                if self.paths[idx].insert(path) {
+                    self.findings[idx].path_count += 1;
+                }
+                // 同一位置既读取了指针又解引用了它时，报告为 dangling pointer
+                let upgrade = finding.kind == FindingKind::DanglingPointer && self.findings[idx].kind == FindingKind::UseAfterDrop;
+                // 正常路径和 unwind 路径上都出现的错误按正常路径报告
+                let normal_path = self.findings[idx].unwind && !finding.unwind;
+                let unwind = self.findings[idx].unwind && finding.unwind;
+                if upgrade || normal_path {
+                    let path_count = self.findings[idx].path_count;
+                    self.findings[idx] = Finding { path_count, ..finding };
+                }
+                self.findings[idx].unwind = unwind;
+                false
+            }
+            None => {
+                self.index.insert(finding.key(), self.findings.len());
+                self.findings.push(finding);
+                self.paths.push(HashSet::from([path]));
+                true
+            }
+        }
+    }
+
+    /// 同一个绑定组被同一次 drop 之后，在嵌套的使用位置上报告的同一类错误
+    /// （如 `md.len()` 中的 `&md` 和 `Deref::deref` 调用）
+    fn nested_duplicate(&self, finding: &Finding) -> Option<usize> {
+        finding.group_root.as_ref()?;
+        self.findings.iter().position(|other| {
+            other.function == finding.function
+                && other.kind == finding.kind
+                && other.drop_span() == finding.drop_span()
+                && other.group_root == finding.group_root
+                && (other.span.contains(finding.span) || finding.span.contains(other.span))
+        })
+    }
+
+    /// 记录一个检测结果；返回是否是新的错误（`unwind = "only"` 时不记录正常路径上的结果）
+    pub fn record(&mut self, finding: Finding) -> bool {
+        if !finding.unwind && crate::config::get().dfs.unwind == crate::options::UnwindPaths::Only {
+            return false;
+        }
+        self.add(finding)
+    }
+
+    /// 按第一次报告的顺序返回合并后的结果
+    pub fn into_findings(self) -> Vec<Finding> {
+        self.findings
+    }
+}
+
+/// 从 Span 解析出的源码区域（行列从 1 开始）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceRegion {
+    pub file: String,
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+/// 把 Span 解析为源码区域；宏展开中的 Span 使用宏调用处的位置
+pub fn resolve_span(tcx: TyCtxt<'_>, span: Span) -> Option<SourceRegion> {
+    let span = span.source_callsite();
+    let (file, start_line, start_column, end_line, end_column) =
+        tcx.sess.source_map().span_to_location_info(span);
+    let file = file?;
+    Some(SourceRegion {
+        file: file.name.prefer_local().to_string(),
+        start_line,
+        start_column,
+        end_line,
+        end_column,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_span::BytePos;
+
+    fn span(lo: u32, hi: u32) -> Span {
+        Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+    }
+
+    fn finding(function: &str, use_span: Span, path: &[u32]) -> Finding {
+        Finding {
+            kind: FindingKind::UseAfterDrop,
+            function: function.to_string(),
+            local: "_1".to_string(),
+            base: Local::from_u32(1),
+            local_ty: None,
+            message: String::new(),
+            mir: MirSite::Statement(String::new()),
+            span: use_span,
+            bb: BasicBlock::from_u32(*path.last().unwrap()),
+            drop: None,
+            is_dropped: true,
+            taint: None,
+            group_root: None,
+            group_members: Vec::new(),
+            path: path.iter().map(|&bb| PathStep { bb: BasicBlock::from_u32(bb), span: use_span }).collect(),
+            path_count: 1,
+            steps: Vec::new(),
+            conditions: Vec::new(),
+            unwind: false,
+        }
+    }
+
+    /// 测试1: 同一错误的不同路径被合并并计数，相同路径不重复计数
+    #[test]
+    fn test_collector_merges_paths() {
+        let mut collector = FindingCollector::default();
+        assert!(collector.add(finding("f", span(10, 12), &[0, 1, 3])));
+        assert!(!collector.add(finding("f", span(10, 12), &[0, 2, 3])));
+        assert!(!collector.add(finding("f", span(10, 12), &[0, 2, 3])));
+        // 不同的使用位置、不同的函数是不同的错误
+        assert!(collector.add(finding("f", span(20, 22), &[0, 4])));
+        assert!(collector.add(finding("g", span(10, 12), &[0, 1, 3])));
+
+        let findings = collector.into_findings();
+        assert_eq!(findings.len(), 3);
+        assert_eq!(findings[0].path_count, 2);
+        assert_eq!(findings[1].path_count, 1);
+        assert_eq!(findings[2].function, "g");
+    }
+
+    /// 测试2: 步骤说明只包含出错变量所在组的事件，最后是使用本身
+    #[test]
+    fn test_trace_steps() {
+        let event = |kind, local: usize, from: Option<usize>, lo| PathEvent {
+            kind,
+            local: PlaceId::local(local),
+            from: from.map(PlaceId::local),
+            bb: BasicBlock::from_u32(lo),
+            span: span(lo, lo + 1),
+        };
+        let events = vec![
+            event(PathEventKind::Bind, 7, Some(1), 1),
+            event(PathEventKind::Move, 9, Some(2), 2),
+            event(PathEventKind::Move, 5, Some(1), 3),
+            event(PathEventKind::Drop, 5, None, 4),
+        ];
+        let members = [1, 5, 7].map(PlaceId::local);
+        let deref = PlaceId::new(7, &[crate::place::PathElem::Deref]);
+        let steps = trace_steps(&events, &members, deref, BasicBlock::from_u32(5), span(5, 6));
+        let messages: Vec<_> = steps.iter().map(|step| step.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["`_1` aliased by `_7` here", "moved into `_5` here", "dropped here", "dereferenced here"]
+        );
+        assert_eq!(steps[2].span, span(4, 5));
+        assert_eq!(steps[2].bb, BasicBlock::from_u32(4));
+        assert_eq!(steps[3].bb, BasicBlock::from_u32(5));
+    }
+
+    /// 测试3: 正常路径和 unwind 路径上的同一个错误按正常路径报告
+    #[test]
+    fn test_collector_prefers_normal_path() {
+        let mut collector = FindingCollector::default();
+        let mut unwind = finding("f", span(10, 12), &[0, 5]);
+        unwind.unwind = true;
+        assert!(collector.add(unwind.clone()));
+        assert!(!collector.add(finding("f", span(10, 12), &[0, 1, 2])));
+        assert!(!collector.add(unwind));
+
+        let findings = collector.into_findings();
+        assert!(!findings[0].unwind);
+        assert_eq!(findings[0].path_count, 2);
+        assert_eq!(findings[0].bb, BasicBlock::from_u32(2));
+    }
+
+    /// 测试4: 同一组在嵌套的使用位置上的结果合并为一个，不同组或不相交的位置不合并
+    #[test]
+    fn test_collector_merges_nested_uses() {
+        let grouped = |use_span, root: &str| Finding { group_root: Some(root.to_string()), ..finding("f", use_span, &[0, 1]) };
+        let mut collector = FindingCollector::default();
+        assert!(collector.add(grouped(span(10, 12), "_1")));
+        assert!(!collector.add(grouped(span(10, 18), "_1")));
+        assert!(collector.add(grouped(span(10, 16), "_2")));
+        assert!(collector.add(grouped(span(20, 22), "_1")));
+        // 不同类型的错误不合并；没有绑定组时只按使用位置合并
+        assert!(collector.add(Finding { kind: FindingKind::DoubleDrop, ..grouped(span(10, 14), "_1") }));
+        assert!(collector.add(finding("f", span(30, 32), &[0, 1])));
+        assert!(collector.add(finding("f", span(30, 34), &[0, 1])));
+
+        let findings = collector.into_findings();
+        assert_eq!(findings.len(), 6);
+        assert_eq!(findings[0].span, span(10, 12));
+    }
+}
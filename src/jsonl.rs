@@ -0,0 +1,109 @@
+//! JSON-lines output: one JSON object per finding.
+//! Lines are appended to the report output as each crate finishes, so the output of a whole
+//! `cargo taint-ana` run can be consumed with `jq` or loaded line by line.
+
+use rustc_middle::ty::TyCtxt;
+use serde::Serialize;
+
+use crate::finding::{resolve_span, Finding, SourceRegion, TraceStep};
+use crate::state::{DropLocation, DropTerminatorKind};
+
+/// 一行 JSON 对应的检测结果
+#[derive(Debug, Serialize)]
+struct JsonFinding<'a> {
+    /// 检测器（`use-after-drop`、`taint`）
+    detector: &'static str,
+    /// 规则 ID（同 SARIF `ruleId`）
+    rule: &'static str,
+    function: &'a str,
+    local: &'a str,
+    local_type: Option<&'a str>,
+    message: &'a str,
+    /// 出错的 MIR 语句或 terminator
+    mir: &'a str,
+    basic_block: String,
+    /// 到达该错误的不同路径数
+    paths: usize,
+    use_span: Option<SourceRegion>,
+    drop: Option<JsonDrop<'a>>,
+    group: JsonGroup<'a>,
+    /// 导致错误的步骤（move/bind/drop/reassign，最后是使用）
+    trace: Vec<JsonStep<'a>>,
+    /// 到达使用位置时假设成立的分支条件
+    conditions: Vec<JsonStep<'a>>,
+    /// 出错位置在 panic 时的 unwind 路径上
+    unwind: bool,
+}
+
+/// 错误步骤中的一步
+#[derive(Debug, Serialize)]
+struct JsonStep<'a> {
+    message: &'a str,
+    basic_block: String,
+    span: Option<SourceRegion>,
+}
+
+impl<'a> JsonStep<'a> {
+    fn new(tcx: TyCtxt<'_>, step: &'a TraceStep) -> Self {
+        Self { message: &step.message, basic_block: format!("{:?}", step.bb), span: resolve_span(tcx, step.span) }
+    }
+}
+
+/// drop 位置
+#[derive(Debug, Serialize)]
+struct JsonDrop<'a> {
+    dropped_by: &'a str,
+    basic_block: String,
+    span: Option<SourceRegion>,
+    /// drop 方式；Statement 中的 drop 为 null
+    terminator_kind: Option<&'a DropTerminatorKind>,
+}
+
+/// 变量所在的绑定组
+#[derive(Debug, Serialize)]
+struct JsonGroup<'a> {
+    root: Option<&'a str>,
+    members: &'a [String],
+}
+
+/// 把一个检测结果序列化为一行 JSON
+pub fn to_json_line(tcx: TyCtxt<'_>, finding: &Finding) -> String {
+    let drop = finding.drop.as_ref().map(|info| {
+        let (bb, span, terminator_kind) = match &info.location {
+            DropLocation::Terminator { bb, span, kind } => (bb, span, Some(kind)),
+            DropLocation::Statement { bb, span, .. } => (bb, span, None),
+        };
+        JsonDrop {
+            dropped_by: &info.dropped_by,
+            basic_block: format!("{:?}", bb),
+            span: resolve_span(tcx, *span),
+            terminator_kind,
+        }
+    });
+    let line = JsonFinding {
+        detector: finding.kind.detector().name(),
+        rule: finding.kind.rule_id(),
+        function: &finding.function,
+        local: &finding.local,
+        local_type: finding.local_ty.as_deref(),
+        message: &finding.message,
+        mir: finding.mir.text(),
+        basic_block: format!("{:?}", finding.bb),
+        paths: finding.path_count,
+        use_span: resolve_span(tcx, finding.span),
+        drop,
+        group: JsonGroup { root: finding.group_root.as_deref(), members: &finding.group_members },
+        trace: finding
+            .steps
+            .iter()
+            .map(|step| JsonStep::new(tcx, step))
+            .collect(),
+        conditions: finding
+            .conditions
+            .iter()
+            .map(|step| JsonStep::new(tcx, step))
+            .collect(),
+        unwind: finding.unwind,
+    };
+    serde_json::to_string(&line).expect("finding is valid JSON")
+}
@@ -4,6 +4,7 @@
 #![feature(box_patterns)]
 
 extern crate rustc_driver;
+extern crate rustc_errors;
 extern crate rustc_interface;
 extern crate rustc_middle;
 extern crate rustc_session;
@@ -11,10 +12,21 @@ extern crate rustc_span;
 extern crate rustc_index;
 
 mod callbacks;
+mod callgraph;
+mod config;
 mod state;
 mod detect;
 mod dfs;
+mod feasibility;
+mod finding;
+mod jsonl;
+mod options;
+mod ownership;
+mod place;
 mod report;
+mod sarif;
+mod summary;
+mod taint;
 
 use log::debug;
 use rustc_session::config::ErrorOutputType;
@@ -49,6 +61,24 @@ fn main() {
         args.remove(1);
     }
 
+    // Parse TAINT_ANA_FLAGS and load taint-ana.toml before compiling so that
+    // configuration errors are reported up front.
+    let options = options::Options::from_env()
+        .unwrap_or_else(|e| handler.early_fatal(format!("taint-ana: {e}")));
+    match config::Config::load(options.config.as_deref()) {
+        Ok(mut config) => {
+            config.apply_options(&options);
+            config::init(config);
+        }
+        Err(e) => handler.early_fatal(format!("taint-ana configuration error: {e}")),
+    }
+    report::set_format(options.format);
+    if let Some(output) = &options.output {
+        if let Err(e) = report::set_output_file(output) {
+            handler.early_fatal(format!("taint-ana: cannot open output file {}: {e}", output.display()));
+        }
+    }
+
     let mut rustc_command_line_arguments = args;
     rustc_driver::install_ice_hook("ice ice ice baby", |_| ());
     let exit_code = rustc_driver::catch_with_exit_code(|| {
@@ -80,10 +110,32 @@ fn main() {
                 // Tell compiler to emit MIR into crate for every function with a body.
                 rustc_command_line_arguments.push(format!("-Z{always_encode_mir}"));
             }
+
+            // Without optimizations rustc strips StorageLive/StorageDead from the MIR; keep them
+            // so that pointers to locals that went out of scope can be detected.
+            let remove_storage_markers = "RemoveStorageMarkers";
+            if !rustc_command_line_arguments
+                .iter()
+                .any(|arg| arg.contains(remove_storage_markers))
+            {
+                rustc_command_line_arguments.push(format!("-Zmir-enable-passes=-{remove_storage_markers}"));
+            }
+
+            // `--jobs`: functions are analyzed on rustc's own thread pool, which only runs
+            // in parallel when the compiler is started with more than one thread.
+            let threads = "threads=";
+            if let Some(jobs) = options.jobs.filter(|&jobs| jobs > 1) {
+                if !rustc_command_line_arguments
+                    .iter()
+                    .any(|arg| arg.contains(threads))
+                {
+                    rustc_command_line_arguments.push(format!("-Z{threads}{jobs}"));
+                }
+            }
         }
 
 
-        let mut callbacks = callbacks::TaintAnaCallbacks::new();
+        let mut callbacks = callbacks::TaintAnaCallbacks::new(options.clone());
         debug!("rustc_command_line_arguments {rustc_command_line_arguments:?}");
         //入口
         rustc_driver::run_compiler(&rustc_command_line_arguments, &mut callbacks);
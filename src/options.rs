@@ -0,0 +1,388 @@
+//! Command-line options passed through `TAINT_ANA_FLAGS`.
+//! `cargo taint-ana [options] -- <cargo args>` collects the options before `--` into
+//! `TAINT_ANA_FLAGS`; both `cargo-taint-ana` (to fail fast) and the `taint-ana` driver parse them
+//! with `Options::parse`. This module has no rustc dependencies so that both binaries can use it.
+
+use std::path::PathBuf;
+
+use regex::Regex;
+use serde::Deserialize;
+
+/// `cargo taint-ana --help` 输出的选项说明
+pub const OPTIONS_HELP: &str = r#"Analysis options:
+    --config <path>          Use this taint-ana.toml instead of the one in the crate root
+    --k <n>                  Number of predecessor blocks in the DFS path context (0 = insensitive)
+    --max-visits <n>         Maximum visits per basic block (>= 1)
+    --engine <engine>        Traversal engine: dfs (path-by-path, default), worklist
+                             (fixpoint with maybe/definitely-dropped states joined at merges)
+    --unwind <mode>          Panic (unwind) paths: include (default, findings on them are marked),
+                             skip (do not follow unwind edges), only (report only findings on them)
+    --time-budget <ms>       Stop analyzing a function after <ms> milliseconds (reported as timed out)
+    --step-budget <n>        Stop analyzing a function after <n> block visits (reported as truncated)
+    --jobs <n>               Analyze up to <n> functions of a crate in parallel (default 1);
+                             reports are printed in the same order for every <n>
+    --only <regex>           Only analyze functions whose path matches the regex
+    --skip <regex>           Skip functions whose path matches the regex (applied after --only)
+    --format <format>        Report format: text, sarif (one merged SARIF 2.1.0 log per run),
+                             json-lines (one JSON object per finding)
+    --output <file>          Write reports to <file> instead of stdout
+    --detectors <list>       Comma-separated detectors to enable: use-after-drop, taint, leak
+                             (default: use-after-drop, taint)
+    --diagnostics <level>    Also emit findings as compiler diagnostics: warn, error
+                             (follows cargo's --message-format, e.g. json)
+    --dfs-stats              Print traversal statistics for every function (with the text
+                             reports; on stderr for the other formats)
+"#;
+
+/// 可以启用/关闭的检测器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Detector {
+    /// drop 之后的使用（包括解引用已释放的指针）
+    UseAfterDrop,
+    /// 污点数据到达 sink
+    Taint,
+    /// 持有所有权的内存在函数返回前既没有释放也没有交出去（需要显式启用）
+    Leak,
+}
+
+impl Detector {
+    pub const ALL: &'static [Detector] = &[Detector::UseAfterDrop, Detector::Taint, Detector::Leak];
+    /// 默认启用的检测器
+    pub const DEFAULT: &'static [Detector] = &[Detector::UseAfterDrop, Detector::Taint];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Detector::UseAfterDrop => "use-after-drop",
+            Detector::Taint => "taint",
+            Detector::Leak => "leak",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|detector| detector.name() == name)
+            .ok_or_else(|| format!("unknown detector `{}`, expected one of: {}", name, Self::names()))
+    }
+
+    fn names() -> String {
+        Self::ALL.iter().map(|d| d.name()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// 报告输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// 人类可读的文本报告（默认）
+    #[default]
+    Text,
+    /// SARIF 2.1.0（`cargo taint-ana` 把所有 crate 的结果合并为一个文件）
+    Sarif,
+    /// 每行一个 JSON 对象
+    JsonLines,
+}
+
+impl OutputFormat {
+    pub const ALL: &'static [OutputFormat] = &[OutputFormat::Text, OutputFormat::Sarif, OutputFormat::JsonLines];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Sarif => "sarif",
+            OutputFormat::JsonLines => "json-lines",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|format| format.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|f| f.name()).collect();
+                format!("unknown format `{}`, expected one of: {}", name, names.join(", "))
+            })
+    }
+}
+
+/// 遍历 MIR 的引擎（对应 taint-ana.toml 的 `dfs.engine`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TraversalEngine {
+    /// 逐条路径 DFS，在分支处保存状态检查点（默认）
+    #[default]
+    Dfs,
+    /// worklist 不动点迭代，在汇合点 join 状态，在循环处加宽
+    Worklist,
+}
+
+impl TraversalEngine {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "dfs" => Ok(TraversalEngine::Dfs),
+            "worklist" => Ok(TraversalEngine::Worklist),
+            _ => Err(format!("unknown engine `{}`, expected one of: dfs, worklist", name)),
+        }
+    }
+}
+
+/// 如何处理 panic 时的 unwind 路径（cleanup block，对应 taint-ana.toml 的 `dfs.unwind`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnwindPaths {
+    /// 与正常路径一起分析，unwind 路径上的结果单独标记（默认）
+    #[default]
+    Include,
+    /// 不走 unwind 出边
+    Skip,
+    /// 只报告 unwind 路径上的结果
+    Only,
+}
+
+impl UnwindPaths {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "include" => Ok(UnwindPaths::Include),
+            "skip" => Ok(UnwindPaths::Skip),
+            "only" => Ok(UnwindPaths::Only),
+            _ => Err(format!("unknown unwind mode `{}`, expected one of: include, skip, only", name)),
+        }
+    }
+}
+
+/// 以编译器诊断输出检测结果时使用的级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticLevel {
+    /// warning，不影响编译结果
+    Warn,
+    /// error，编译失败
+    Error,
+}
+
+impl DiagnosticLevel {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "warn" => Ok(DiagnosticLevel::Warn),
+            "error" => Ok(DiagnosticLevel::Error),
+            _ => Err(format!("unknown diagnostic level `{}`, expected one of: warn, error", name)),
+        }
+    }
+}
+
+/// 解析后的选项；未指定的选项为 None，使用 taint-ana.toml 或默认值
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub config: Option<PathBuf>,
+    pub k_predecessor: Option<usize>,
+    pub max_visits_per_block: Option<usize>,
+    pub engine: Option<TraversalEngine>,
+    pub unwind: Option<UnwindPaths>,
+    pub time_budget_ms: Option<u64>,
+    pub step_budget: Option<usize>,
+    /// 并行分析的线程数；为 None 时顺序分析
+    pub jobs: Option<usize>,
+    pub only: Option<Regex>,
+    pub skip: Option<Regex>,
+    pub format: OutputFormat,
+    pub output: Option<PathBuf>,
+    pub detectors: Option<Vec<Detector>>,
+    /// 为 None 时不输出编译器诊断
+    pub diagnostics: Option<DiagnosticLevel>,
+    /// 输出每个函数的遍历统计
+    pub dfs_stats: bool,
+}
+
+/// `cargo taint-ana` 告诉 driver 把每个 crate 的 SARIF 日志写到哪个目录的环境变量
+pub const SARIF_DIR_ENV: &str = "TAINT_ANA_SARIF_DIR";
+
+/// `cargo taint-ana` 在 `TAINT_ANA_FLAGS` 中每个选项之后加上的分隔符（选项值可以包含空格）
+pub const FLAG_SEPARATOR: char = '\x1f';
+
+/// 把选项列表编码为 `TAINT_ANA_FLAGS`（只有 `cargo taint-ana` 使用，driver 中只在测试里和 `split_flags` 对照）
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn join_flags(flags: &[String]) -> String {
+    flags.iter().map(|flag| format!("{}{}", flag, FLAG_SEPARATOR)).collect()
+}
+
+/// 拆分 `TAINT_ANA_FLAGS`：含有 `FLAG_SEPARATOR` 时按它拆分，否则（手写的环境变量）按空白拆分
+pub fn split_flags(flags: &str) -> Vec<String> {
+    if flags.contains(FLAG_SEPARATOR) {
+        flags.split_terminator(FLAG_SEPARATOR).map(str::to_string).collect()
+    } else {
+        flags.split_whitespace().map(str::to_string).collect()
+    }
+}
+
+impl Options {
+    /// 解析 `TAINT_ANA_FLAGS`
+    pub fn from_env() -> Result<Self, String> {
+        let flags = std::env::var("TAINT_ANA_FLAGS").unwrap_or_default();
+        Self::parse(&split_flags(&flags))
+    }
+
+    /// 解析选项列表，支持 `--flag value` 和 `--flag=value` 两种写法；未知选项返回错误
+    pub fn parse(flags: &[String]) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut iter = flags.iter();
+        while let Some(flag) = iter.next() {
+            let (name, inline_value) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (flag.as_str(), None),
+            };
+            let mut value = || -> Result<String, String> {
+                inline_value
+                    .clone()
+                    .or_else(|| iter.next().cloned())
+                    .ok_or_else(|| format!("option `{}` requires a value", name))
+            };
+
+            match name {
+                "--config" => options.config = Some(PathBuf::from(value()?)),
+                "--k" => options.k_predecessor = Some(parse_number(name, &value()?)?),
+                "--max-visits" => {
+                    let max_visits = parse_number(name, &value()?)?;
+                    if max_visits == 0 {
+                        return Err("option `--max-visits` must be at least 1".to_string());
+                    }
+                    options.max_visits_per_block = Some(max_visits);
+                }
+                "--time-budget" => {
+                    let ms = parse_number(name, &value()?)?;
+                    if ms == 0 {
+                        return Err("option `--time-budget` must be at least 1".to_string());
+                    }
+                    options.time_budget_ms = Some(ms as u64);
+                }
+                "--step-budget" => {
+                    let steps = parse_number(name, &value()?)?;
+                    if steps == 0 {
+                        return Err("option `--step-budget` must be at least 1".to_string());
+                    }
+                    options.step_budget = Some(steps);
+                }
+                "--jobs" => {
+                    let jobs = parse_number(name, &value()?)?;
+                    if jobs == 0 {
+                        return Err("option `--jobs` must be at least 1".to_string());
+                    }
+                    options.jobs = Some(jobs);
+                }
+                "--engine" => options.engine = Some(TraversalEngine::from_name(&value()?)?),
+                "--unwind" => options.unwind = Some(UnwindPaths::from_name(&value()?)?),
+                "--only" => options.only = Some(parse_regex(name, &value()?)?),
+                "--skip" => options.skip = Some(parse_regex(name, &value()?)?),
+                "--format" => options.format = OutputFormat::from_name(&value()?)?,
+                "--output" => options.output = Some(PathBuf::from(value()?)),
+                "--detectors" => {
+                    let detectors = value()?
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                        .map(Detector::from_name)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    options.detectors = Some(detectors);
+                }
+                "--diagnostics" => options.diagnostics = Some(DiagnosticLevel::from_name(&value()?)?),
+                "--dfs-stats" if inline_value.is_none() => options.dfs_stats = true,
+                _ => return Err(format!("unknown option `{}`\n\n{}", flag, OPTIONS_HELP)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// 函数路径是否通过 `--only`/`--skip` 过滤
+    pub fn is_function_selected(&self, fn_path: &str) -> bool {
+        self.only.as_ref().is_none_or(|only| only.is_match(fn_path))
+            && !self.skip.as_ref().is_some_and(|skip| skip.is_match(fn_path))
+    }
+}
+
+fn parse_number(name: &str, value: &str) -> Result<usize, String> {
+    value
+        .parse::<usize>()
+        .map_err(|_| format!("option `{}` expects a non-negative integer, got `{}`", name, value))
+}
+
+fn parse_regex(name: &str, value: &str) -> Result<Regex, String> {
+    Regex::new(value).map_err(|e| format!("option `{}`: invalid regex `{}`: {}", name, value, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(flags: &[&str]) -> Result<Options, String> {
+        Options::parse(&flags.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    /// 测试1: 没有选项时全部为默认值
+    #[test]
+    fn test_empty_flags() {
+        let options = parse(&[]).unwrap();
+        assert!(options.config.is_none());
+        assert!(options.k_predecessor.is_none());
+        assert!(options.detectors.is_none());
+        assert_eq!(options.format, OutputFormat::Text);
+        assert!(!options.dfs_stats);
+        assert!(options.is_function_selected("any::path"));
+    }
+
+    /// 测试2: 两种写法都能解析
+    #[test]
+    fn test_parse_all_options() {
+        let options = parse(&[
+            "--config", "cfg.toml", "--k=3", "--max-visits", "20", "--only", "^my_crate::",
+            "--skip=::tests::", "--format", "sarif", "--output", "out.txt", "--detectors", "taint,use-after-drop",
+            "--dfs-stats",
+        ])
+        .unwrap();
+        assert_eq!(options.config, Some(PathBuf::from("cfg.toml")));
+        assert_eq!(options.k_predecessor, Some(3));
+        assert_eq!(options.max_visits_per_block, Some(20));
+        assert_eq!(options.format, OutputFormat::Sarif);
+        assert_eq!(options.output, Some(PathBuf::from("out.txt")));
+        assert_eq!(options.detectors, Some(vec![Detector::Taint, Detector::UseAfterDrop]));
+        assert!(options.dfs_stats);
+        assert!(options.is_function_selected("my_crate::foo"));
+        assert!(!options.is_function_selected("my_crate::tests::foo"));
+        assert!(!options.is_function_selected("other::foo"));
+    }
+
+    /// 测试3: 未知选项和非法值被拒绝
+    #[test]
+    fn test_reject_invalid_flags() {
+        assert!(parse(&["--verbose"]).unwrap_err().contains("unknown option `--verbose`"));
+        assert!(parse(&["--k"]).unwrap_err().contains("requires a value"));
+        assert!(parse(&["--k", "two"]).unwrap_err().contains("non-negative integer"));
+        assert!(parse(&["--max-visits", "0"]).is_err());
+        assert!(parse(&["--only", "("]).unwrap_err().contains("invalid regex"));
+        assert!(parse(&["--format", "xml"]).unwrap_err().contains("unknown format `xml`"));
+        assert_eq!(parse(&["--format=json-lines"]).unwrap().format, OutputFormat::JsonLines);
+        assert!(parse(&["--detectors", "race"]).unwrap_err().contains("unknown detector `race`"));
+        assert_eq!(parse(&["--detectors=leak"]).unwrap().detectors, Some(vec![Detector::Leak]));
+        assert!(parse(&["--diagnostics", "deny"]).unwrap_err().contains("unknown diagnostic level"));
+        assert_eq!(parse(&["--diagnostics=warn"]).unwrap().diagnostics, Some(DiagnosticLevel::Warn));
+        assert!(parse(&["--engine", "bfs"]).unwrap_err().contains("unknown engine `bfs`"));
+        assert_eq!(parse(&["--engine=worklist"]).unwrap().engine, Some(TraversalEngine::Worklist));
+        assert!(parse(&["--unwind", "ignore"]).unwrap_err().contains("unknown unwind mode `ignore`"));
+        assert_eq!(parse(&["--unwind=skip"]).unwrap().unwind, Some(UnwindPaths::Skip));
+        assert!(parse(&["--jobs", "0"]).unwrap_err().contains("at least 1"));
+        assert_eq!(parse(&["--jobs=4"]).unwrap().jobs, Some(4));
+        assert!(parse(&["--time-budget", "0"]).unwrap_err().contains("at least 1"));
+        let options = parse(&["--time-budget=500", "--step-budget", "10000"]).unwrap();
+        assert_eq!(options.time_budget_ms, Some(500));
+        assert_eq!(options.step_budget, Some(10000));
+    }
+
+    /// 测试4: 选项值中的空格在 TAINT_ANA_FLAGS 中保留，手写的环境变量按空白拆分
+    #[test]
+    fn test_split_flags() {
+        let flags: Vec<String> = ["--only", "^my crate::", "--output=my dir/out.txt"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(split_flags(&join_flags(&flags)), flags);
+        assert_eq!(split_flags(&join_flags(&flags[..1])), vec!["--only"]);
+        assert!(split_flags(&join_flags(&[])).is_empty());
+        assert_eq!(split_flags(" --k 3  --format=sarif "), vec!["--k", "3", "--format=sarif"]);
+    }
+}
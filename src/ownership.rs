@@ -0,0 +1,160 @@
+//! Ownership transfer through raw pointers.
+//! `into_raw` gives up ownership of an allocation and returns a raw pointer that carries it;
+//! `from_raw` (and `from_raw_parts`) reclaims it. Calls are resolved by the self type of the
+//! inherent impl (`Box`, `CString`, `Rc`, `Arc`, `Vec`, `String`), so user methods that happen
+//! to share these names are not treated as ownership transfers. `ManuallyDrop::new` gives up
+//! ownership the same way (the wrapper never drops its contents) and `into_inner`/`take` reclaim it.
+//! `ptr::write` overwrites its destination without dropping the old value.
+//! Drop semantics of `mem::drop`, `mem::forget`, `ptr::drop_in_place` and `ManuallyDrop::drop` are
+//! resolved by `DefId` (diagnostic and lang items) as well, not by path, and so are `Deref::deref`
+//! and `DerefMut::deref_mut`, whose result points into `self`.
+
+use rustc_middle::ty::TyCtxt;
+use rustc_span::def_id::DefId;
+use rustc_span::sym;
+
+/// 所有权转移的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transfer {
+    /// 放弃所有权，返回拥有这块内存的裸指针（如 `Box::into_raw`）或不会被 drop 的包装（`ManuallyDrop::new`）
+    IntoRaw,
+    /// 从裸指针重新取得所有权（如 `Box::from_raw`、`Vec::from_raw_parts`）
+    FromRaw,
+}
+
+impl Transfer {
+    /// 按所属类型和方法名判断转移方向
+    fn of_method(owner: &str, name: &str) -> Option<Self> {
+        if owner == "ManuallyDrop" {
+            return match name {
+                "new" => Some(Transfer::IntoRaw),
+                "into_inner" | "take" => Some(Transfer::FromRaw),
+                _ => None,
+            };
+        }
+        match name {
+            "into_raw" | "into_raw_with_allocator" | "into_non_null" | "into_non_null_with_allocator"
+            | "into_raw_parts" | "into_raw_parts_with_alloc" => Some(Transfer::IntoRaw),
+            "from_raw" | "from_raw_in" | "from_non_null" | "from_non_null_in" | "from_raw_parts"
+            | "from_raw_parts_in" => Some(Transfer::FromRaw),
+            _ => None,
+        }
+    }
+}
+
+/// 一次所有权转移调用，如 `Box::into_raw`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnershipCall {
+    pub transfer: Transfer,
+    /// 用于报告的名字，如 "Box::from_raw"
+    pub name: String,
+}
+
+/// 判断被调函数是否是所有权转移调用
+pub fn classify(tcx: TyCtxt<'_>, def_id: DefId) -> Option<OwnershipCall> {
+    let method = tcx.item_name(def_id);
+    let impl_id = tcx.inherent_impl_of_assoc(def_id)?;
+    let adt = tcx.type_of(impl_id).skip_binder().ty_adt_def()?;
+    let owner = owner_name(tcx, adt.did())?;
+    let transfer = Transfer::of_method(owner, method.as_str())?;
+    Some(OwnershipCall { transfer, name: format!("{}::{}", owner, method) })
+}
+
+/// 标准库中有 drop 语义的函数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropCall {
+    /// `mem::drop`：drop move 进来的参数；参数是引用、裸指针这类不需要 drop 的值时什么也不做
+    Value,
+    /// `ptr::drop_in_place`、`ManuallyDrop::drop`：就地 drop 参数指向的值
+    Pointee,
+    /// `mem::forget`：取走参数而不 drop
+    Forget,
+}
+
+/// 判断被调函数是否是有 drop 语义的标准库函数
+pub fn drop_call(tcx: TyCtxt<'_>, def_id: DefId) -> Option<DropCall> {
+    match tcx.get_diagnostic_name(def_id) {
+        Some(sym::mem_drop) => return Some(DropCall::Value),
+        Some(sym::mem_forget) => return Some(DropCall::Forget),
+        _ => {}
+    }
+    if tcx.lang_items().drop_in_place_fn() == Some(def_id) {
+        return Some(DropCall::Pointee);
+    }
+    let impl_id = tcx.inherent_impl_of_assoc(def_id)?;
+    let adt = tcx.type_of(impl_id).skip_binder().ty_adt_def()?;
+    (tcx.lang_items().manually_drop() == Some(adt.did()) && tcx.item_name(def_id).as_str() == "drop")
+        .then_some(DropCall::Pointee)
+}
+
+/// 判断被调函数是否是 `Deref::deref`/`DerefMut::deref_mut` 的实现：返回值指向 self 里面的值
+pub fn is_deref_method(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    let lang_items = tcx.lang_items();
+    tcx.trait_of_assoc(def_id)
+        .is_some_and(|trait_id| Some(trait_id) == lang_items.deref_trait() || Some(trait_id) == lang_items.deref_mut_trait())
+}
+
+/// 判断被调函数是否是 `ptr::write` 系列：写入目标位置而不 drop 原来的值
+pub fn is_overwrite(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    if matches!(
+        tcx.get_diagnostic_name(def_id),
+        Some(sym::ptr_write | sym::ptr_write_unaligned | sym::ptr_write_volatile)
+    ) {
+        return true;
+    }
+    // `<*mut T>::write` 等方法没有 diagnostic item
+    is_raw_pointer_method(tcx, def_id)
+        && matches!(tcx.item_name(def_id).as_str(), "write" | "write_unaligned" | "write_volatile")
+}
+
+/// 裸指针的固有方法（`is_null`、`add`、`cast` 等），不会取得指针指向的内存的所有权
+pub fn is_raw_pointer_method(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    tcx.inherent_impl_of_assoc(def_id)
+        .is_some_and(|impl_id| tcx.type_of(impl_id).skip_binder().is_raw_ptr())
+}
+
+/// 拥有堆内存、支持 into_raw/from_raw 的类型，以及 `ManuallyDrop`
+fn owner_name(tcx: TyCtxt<'_>, adt: DefId) -> Option<&'static str> {
+    if tcx.lang_items().owned_box() == Some(adt) {
+        return Some("Box");
+    }
+    if tcx.lang_items().manually_drop() == Some(adt) {
+        return Some("ManuallyDrop");
+    }
+    match tcx.get_diagnostic_name(adt)? {
+        sym::cstring_type => Some("CString"),
+        sym::Rc => Some("Rc"),
+        sym::Arc => Some("Arc"),
+        sym::Vec => Some("Vec"),
+        sym::String => Some("String"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试1: into_raw/from_raw 系列方法的方向
+    #[test]
+    fn test_transfer_of_method() {
+        assert_eq!(Transfer::of_method("Box", "into_raw"), Some(Transfer::IntoRaw));
+        assert_eq!(Transfer::of_method("Vec", "into_raw_parts"), Some(Transfer::IntoRaw));
+        assert_eq!(Transfer::of_method("Box", "from_raw"), Some(Transfer::FromRaw));
+        assert_eq!(Transfer::of_method("Vec", "from_raw_parts_in"), Some(Transfer::FromRaw));
+        assert_eq!(Transfer::of_method("Vec", "as_ptr"), None);
+        assert_eq!(Transfer::of_method("Box", "leak"), None);
+        assert_eq!(Transfer::of_method("Box", "new"), None);
+    }
+
+    /// 测试2: ManuallyDrop::new 放弃所有权，into_inner/take 取回
+    #[test]
+    fn test_manually_drop_transfer() {
+        assert_eq!(Transfer::of_method("ManuallyDrop", "new"), Some(Transfer::IntoRaw));
+        assert_eq!(Transfer::of_method("ManuallyDrop", "into_inner"), Some(Transfer::FromRaw));
+        assert_eq!(Transfer::of_method("ManuallyDrop", "take"), Some(Transfer::FromRaw));
+        assert_eq!(Transfer::of_method("ManuallyDrop", "drop"), None);
+        assert_eq!(Transfer::of_method("ManuallyDrop", "into_raw"), None);
+        assert_eq!(Transfer::of_method("ManuallyDrop", "deref"), None);
+    }
+}
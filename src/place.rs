@@ -0,0 +1,204 @@
+//! Interned place identifiers.
+//! A `PlaceId` names a MIR local together with the part of its projection that the analysis
+//! tracks (struct fields, enum variant fields and dereferences). Keys are interned per thread, so
+//! ids are `Copy`, cheap to hash and compare, and prefix/parent queries do not allocate strings.
+//! The `Display` form (`_1.0`, `(_1 as 0).0`, `*_1`, `_1.0@deref`) is only built for reports.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+/// 被跟踪的投影元素
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PathElem {
+    /// 结构体/元组字段
+    Field(u32),
+    /// 枚举字段（Downcast + Field）
+    VariantField { variant: u32, field: u32 },
+    /// 解引用
+    Deref,
+}
+
+/// 被 intern 的 place：基础 local + 投影路径
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PlaceKey {
+    local: u32,
+    projection: Box<[PathElem]>,
+}
+
+#[derive(Default)]
+struct PlaceInterner {
+    keys: Vec<PlaceKey>,
+    ids: HashMap<PlaceKey, PlaceId>,
+}
+
+thread_local! {
+    /// 当前线程的 place 表（一个函数总是在同一个线程上分析）
+    static INTERNER: RefCell<PlaceInterner> = RefCell::new(PlaceInterner::default());
+}
+
+/// 被 intern 的 place ID
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PlaceId(u32);
+
+impl PlaceId {
+    /// 不带投影的 local（`_n`）
+    pub fn local(local: usize) -> Self {
+        Self::new(local, &[])
+    }
+
+    pub fn new(local: usize, projection: &[PathElem]) -> Self {
+        let key = PlaceKey { local: local as u32, projection: projection.into() };
+        INTERNER.with(|interner| {
+            let mut interner = interner.borrow_mut();
+            if let Some(&id) = interner.ids.get(&key) {
+                return id;
+            }
+            let id = PlaceId(interner.keys.len() as u32);
+            interner.keys.push(key.clone());
+            interner.ids.insert(key, id);
+            id
+        })
+    }
+
+    fn with_key<R>(self, f: impl FnOnce(&PlaceKey) -> R) -> R {
+        INTERNER.with(|interner| f(&interner.borrow().keys[self.0 as usize]))
+    }
+
+    /// 基础 local 的编号（`_3.1`、`*_3` 都是 3）
+    pub fn local_index(self) -> usize {
+        self.with_key(|key| key.local as usize)
+    }
+
+    /// 基础 local 本身
+    pub fn base(self) -> Self {
+        Self::local(self.local_index())
+    }
+
+    /// 在末尾追加一个投影元素
+    pub fn project(self, elem: PathElem) -> Self {
+        let (local, mut projection) = self.with_key(|key| (key.local as usize, key.projection.to_vec()));
+        projection.push(elem);
+        Self::new(local, &projection)
+    }
+
+    /// 去掉最后一个投影元素；基础 local 没有 parent
+    pub fn parent(self) -> Option<Self> {
+        let (local, projection) = self.with_key(|key| (key.local as usize, key.projection.to_vec()));
+        let (_, rest) = projection.split_last()?;
+        Some(Self::new(local, rest))
+    }
+
+    /// `self` 是否是 `other` 的前缀（包括相等）
+    pub fn is_prefix_of(self, other: Self) -> bool {
+        self.with_key(|prefix| {
+            other.with_key(|key| prefix.local == key.local && key.projection.starts_with(&prefix.projection))
+        })
+    }
+
+    /// 是否经过解引用
+    pub fn is_deref(self) -> bool {
+        self.with_key(|key| key.projection.contains(&PathElem::Deref))
+    }
+
+    /// 每次解引用之前的 place，即被解引用的指针（`*_21.0` → `_21`，`_21.0@deref` → `_21.0`）
+    pub fn deref_bases(self) -> Vec<Self> {
+        let (local, projection) = self.with_key(|key| (key.local as usize, key.projection.to_vec()));
+        projection
+            .iter()
+            .enumerate()
+            .filter(|(_, elem)| **elem == PathElem::Deref)
+            .map(|(idx, _)| Self::new(local, &projection[..idx]))
+            .collect()
+    }
+}
+
+impl fmt::Display for PlaceId {
+    /// 开头的解引用写成 `*` 前缀，字段之后的解引用写成 `@deref` 后缀
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = self.with_key(|key| {
+            let leading = key.projection.iter().take_while(|elem| **elem == PathElem::Deref).count();
+            let mut text = format!("{}_{}", "*".repeat(leading), key.local);
+            for elem in &key.projection[leading..] {
+                text = match elem {
+                    PathElem::Field(field) => format!("{}.{}", text, field),
+                    PathElem::VariantField { variant, field } => format!("({} as {}).{}", text, variant, field),
+                    PathElem::Deref => format!("{}@deref", text),
+                };
+            }
+            text
+        });
+        f.write_str(&text)
+    }
+}
+
+impl fmt::Debug for PlaceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试1: 相同的 place 得到相同的 ID
+    #[test]
+    fn test_interning() {
+        let field = PlaceId::new(1, &[PathElem::Field(0)]);
+        assert_eq!(field, PlaceId::local(1).project(PathElem::Field(0)));
+        assert_ne!(field, PlaceId::local(1));
+        assert_ne!(field, PlaceId::new(1, &[PathElem::Field(1)]));
+    }
+
+    /// 测试2: 显示形式与报告中使用的写法一致
+    #[test]
+    fn test_display() {
+        use PathElem::*;
+        assert_eq!(PlaceId::local(3).to_string(), "_3");
+        assert_eq!(PlaceId::new(1, &[Field(3), Field(4), Field(5)]).to_string(), "_1.3.4.5");
+        assert_eq!(PlaceId::new(1, &[VariantField { variant: 1, field: 0 }]).to_string(), "(_1 as 1).0");
+        assert_eq!(PlaceId::new(1, &[Deref, Deref]).to_string(), "**_1");
+        assert_eq!(PlaceId::new(1, &[Deref, Field(0)]).to_string(), "*_1.0");
+        assert_eq!(PlaceId::new(1, &[Field(0), Deref]).to_string(), "_1.0@deref");
+        assert_eq!(format!("{:?}", PlaceId::new(2, &[Deref])), "*_2");
+    }
+
+    /// 测试3: 字段、解引用路径都归属到基础 local
+    #[test]
+    fn test_base_local() {
+        use PathElem::*;
+        assert_eq!(PlaceId::new(12, &[Field(1), Field(0)]).local_index(), 12);
+        assert_eq!(PlaceId::new(4, &[Deref]).base(), PlaceId::local(4));
+        assert_eq!(PlaceId::new(5, &[Field(0), Deref]).base(), PlaceId::local(5));
+        assert_eq!(PlaceId::new(1, &[VariantField { variant: 0, field: 0 }]).local_index(), 1);
+    }
+
+    /// 测试4: parent 和前缀查询
+    #[test]
+    fn test_parent_and_prefix() {
+        use PathElem::*;
+        let place = PlaceId::new(1, &[Field(0), Deref, Field(2)]);
+        assert_eq!(place.parent(), Some(PlaceId::new(1, &[Field(0), Deref])));
+        assert_eq!(PlaceId::local(1).parent(), None);
+        assert!(PlaceId::local(1).is_prefix_of(place));
+        assert!(PlaceId::new(1, &[Field(0)]).is_prefix_of(place));
+        assert!(place.is_prefix_of(place));
+        assert!(!PlaceId::new(1, &[Field(1)]).is_prefix_of(place));
+        assert!(!PlaceId::local(2).is_prefix_of(place));
+    }
+
+    /// 测试5: 解引用依赖的指针
+    #[test]
+    fn test_deref_bases() {
+        use PathElem::*;
+        assert_eq!(PlaceId::new(21, &[Deref]).deref_bases(), vec![PlaceId::local(21)]);
+        assert_eq!(
+            PlaceId::new(21, &[Deref, Deref]).deref_bases(),
+            vec![PlaceId::local(21), PlaceId::new(21, &[Deref])]
+        );
+        assert_eq!(PlaceId::new(21, &[Field(0), Deref]).deref_bases(), vec![PlaceId::new(21, &[Field(0)])]);
+        assert!(PlaceId::new(21, &[Field(0)]).deref_bases().is_empty());
+        assert!(PlaceId::new(21, &[Field(0), Deref]).is_deref());
+    }
+}
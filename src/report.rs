@@ -1,12 +1,214 @@
 //! Unified reporting module for taint analysis output.
 //! Provides structured error reporting with MIR context.
 
-use rustc_middle::mir::{Body, Statement, Terminator, BasicBlock, Local};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use rustc_errors::{Diag, EmissionGuarantee};
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::mir::{Body, Statement, Terminator, TerminatorKind, BasicBlock, Local, Location, Place};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
 use rustc_index::Idx;
 use log::{info, error};
 
+use crate::dfs::{AnalysisOutcome, DfsStats};
+use crate::finding::{Finding, FindingCollector, FindingKind, MirSite, TaintFlow};
+use crate::options::{DiagnosticLevel, OutputFormat};
+use crate::place::PlaceId;
 use crate::state::BindingManager;
 
+/// Report output file set by `--output`; reports go to stdout when unset
+static OUTPUT: OnceLock<Mutex<File>> = OnceLock::new();
+
+/// Report format set by `--format`
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Set the report format; must be called before the analysis starts
+pub fn set_format(format: OutputFormat) {
+    if FORMAT.set(format).is_err() {
+        eprintln!("⚠️  Warning: report format already set, ignoring {}", format.name());
+    }
+}
+
+/// Whether the human-readable reports are printed while analyzing
+fn is_text_format() -> bool {
+    FORMAT.get().copied().unwrap_or_default() == OutputFormat::Text
+}
+
+/// Send reports to `path` instead of stdout.
+/// The file is opened in append mode because every crate of a cargo run is analyzed by a
+/// separate driver process; `cargo taint-ana` truncates it once at the start of the run.
+pub fn set_output_file(path: &Path) -> std::io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    if OUTPUT.set(Mutex::new(file)).is_err() {
+        eprintln!("⚠️  Warning: report output already set, ignoring {}", path.display());
+    }
+    Ok(())
+}
+
+/// Write one report line to the configured output
+fn write_line(args: std::fmt::Arguments) {
+    match OUTPUT.get() {
+        Some(file) => {
+            let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+            if let Err(e) = writeln!(file, "{}", args) {
+                eprintln!("⚠️  Warning: failed to write report: {}", e);
+            }
+        }
+        None => println!("{}", args),
+    }
+}
+
+/// `println!` for report output
+macro_rules! outln {
+    ($($arg:tt)*) => {
+        write_line(format_args!($($arg)*))
+    };
+}
+
+/// A function whose traversal stopped before covering all paths
+/// (time or step budget exhausted, or blocks skipped at `max_visits_per_block`)
+#[derive(Debug, Clone)]
+pub struct IncompleteCoverage {
+    pub function: String,
+    pub outcome: AnalysisOutcome,
+    pub reason: String,
+}
+
+/// Write the machine-readable reports of a crate once all its functions are analyzed.
+/// Text reports are printed at the end of each function and need nothing here.
+pub fn emit_findings(tcx: TyCtxt<'_>, crate_name: &str, findings: &[Finding], incomplete: &[IncompleteCoverage]) {
+    match FORMAT.get().copied().unwrap_or_default() {
+        OutputFormat::Text => {}
+        OutputFormat::JsonLines => {
+            for finding in findings {
+                outln!("{}", crate::jsonl::to_json_line(tcx, finding));
+            }
+        }
+        OutputFormat::Sarif => {
+            let log = crate::sarif::to_sarif_log(tcx, findings, incomplete);
+            let content = serde_json::to_string_pretty(&log).expect("SARIF log is valid JSON");
+            if let Some(dir) = std::env::var_os(crate::options::SARIF_DIR_ENV) {
+                // `cargo taint-ana` merges the logs of all crates after the build
+                let path = Path::new(&dir).join(format!("{}-{}.sarif", crate_name, std::process::id()));
+                if let Err(e) = std::fs::write(&path, content) {
+                    eprintln!("⚠️  Warning: failed to write SARIF log to {}: {}", path.display(), e);
+                }
+                return;
+            }
+            if let Some(file) = OUTPUT.get() {
+                // The output file is opened in append mode; a SARIF log must be the whole file
+                let file = file.lock().unwrap_or_else(|e| e.into_inner());
+                if let Err(e) = file.set_len(0) {
+                    eprintln!("⚠️  Warning: failed to truncate report output: {}", e);
+                }
+            }
+            outln!("{}", content);
+        }
+    }
+}
+
+/// List the functions whose analysis did not cover all paths: a clean result for them
+/// means little. Printed after the text reports; with the machine-readable formats the
+/// list goes to stderr (SARIF also records it as tool notifications).
+pub fn report_incomplete_coverage(incomplete: &[IncompleteCoverage], analyzed: usize) {
+    if incomplete.is_empty() {
+        return;
+    }
+    if is_text_format() {
+        outln!("\n⚠️  覆盖不完整的函数 ({}/{}):", incomplete.len(), analyzed);
+        for entry in incomplete {
+            outln!("   {} [{}]: {}", entry.function, entry.outcome.name(), entry.reason);
+        }
+    } else {
+        eprintln!("⚠️  Warning: analysis was incomplete for {} of {} functions:", incomplete.len(), analyzed);
+        for entry in incomplete {
+            eprintln!("   {} [{}]: {}", entry.function, entry.outcome.name(), entry.reason);
+        }
+    }
+}
+
+/// Emit every finding as a compiler diagnostic (`--diagnostics warn|error`).
+/// Going through `tcx.dcx()` makes the findings follow the rustc/cargo error format,
+/// including `--message-format=json`.
+pub fn emit_diagnostics(tcx: TyCtxt<'_>, level: DiagnosticLevel, findings: &[Finding]) {
+    let dcx = tcx.dcx();
+    for finding in findings {
+        match level {
+            DiagnosticLevel::Warn => {
+                let mut diag = dcx.struct_span_warn(finding.span, finding.message.clone());
+                decorate_diagnostic(&mut diag, finding);
+                diag.emit();
+            }
+            DiagnosticLevel::Error => {
+                let mut diag = dcx.struct_span_err(finding.span, finding.message.clone());
+                decorate_diagnostic(&mut diag, finding);
+                diag.emit();
+            }
+        }
+    }
+}
+
+/// Labels and notes shared by warnings and errors
+fn decorate_diagnostic<G: EmissionGuarantee>(diag: &mut Diag<'_, G>, finding: &Finding) {
+    match finding.kind {
+        FindingKind::UseAfterDrop => {
+            diag.span_label(finding.span, "value used here after drop");
+        }
+        FindingKind::DanglingPointer => {
+            diag.span_label(finding.span, "dangling pointer dereferenced here");
+        }
+        // 逃逸到静态内存时使用位置就是离开作用域的位置，由下面的 drop 标签说明
+        FindingKind::UseAfterScope if finding.drop_span() == Some(finding.span) => {}
+        FindingKind::UseAfterScope => {
+            diag.span_label(finding.span, "stack memory used here after its scope ended");
+        }
+        FindingKind::DanglingReturn => {
+            diag.span_label(finding.span, "dangling pointer returned here");
+        }
+        FindingKind::DoubleOwnership => {
+            diag.span_label(finding.span, "ownership reclaimed here");
+        }
+        FindingKind::DoubleFree => {
+            diag.span_label(finding.span, "memory freed again here");
+        }
+        FindingKind::DoubleDrop => {
+            diag.span_label(finding.span, "value dropped again here");
+        }
+        FindingKind::Leak => {
+            diag.span_label(finding.span, "owned memory leaked here");
+        }
+        FindingKind::TaintedSink => {
+            diag.span_label(finding.span, "tainted data reaches this sink");
+        }
+    }
+    if let Some(drop_span) = finding.drop_span() {
+        diag.span_label(drop_span, finding.drop_label());
+    }
+    // Moves, aliases and reassignments leading to the error (the drop and the use are labeled above)
+    if let Some((_, events)) = finding.steps.split_last() {
+        for step in events.iter().filter(|step| Some(step.span) != finding.drop_span()) {
+            diag.span_label(step.span, step.message.clone());
+        }
+    }
+    match &finding.local_ty {
+        Some(ty) => diag.note(format!("in function `{}`, `{}` has type `{}`", finding.function, finding.local, ty)),
+        None => diag.note(format!("in function `{}`", finding.function)),
+    };
+    diag.note(format!("steps: {}", finding.trace()));
+    if !finding.conditions.is_empty() {
+        let conditions: Vec<&str> = finding.conditions.iter().map(|step| step.message.as_str()).collect();
+        diag.note(format!("assuming {}", conditions.join(", ")));
+    }
+    if finding.unwind {
+        diag.note("only on the unwind path taken when a panic occurs");
+    }
+    diag.note(format!("reported by taint-ana ({})", finding.kind.rule_id()));
+}
+
 /// Check if info-level logging is enabled
 fn is_info_enabled() -> bool {
     log::log_enabled!(log::Level::Info)
@@ -19,19 +221,128 @@ fn is_debug_enabled() -> bool {
 
 /// Output function analysis start information
 pub fn report_function_start(fn_name: &str, body: &Body) {
-    if is_info_enabled() {
-        println!("\n{}", "=".repeat(60));
-        println!("🔍 分析函数: {}", fn_name);
-        println!("   局部变量数: {}", body.local_decls.len());
-        println!("   基本块数: {}", body.basic_blocks.len());
-        println!("{}\n", "=".repeat(60));
+    if is_info_enabled() && is_text_format() {
+        outln!("\n{}", "=".repeat(60));
+        outln!("🔍 分析函数: {}", fn_name);
+        outln!("   局部变量数: {}", body.local_decls.len());
+        outln!("   基本块数: {}", body.basic_blocks.len());
+        outln!("{}\n", "=".repeat(60));
     }
 }
 
 /// Output function analysis end
-pub fn report_function_end(fn_name: &str) {
-    if is_info_enabled() {
-        //println!("✅ 完成分析: {}\n", fn_name);
+pub fn report_function_end(fn_name: &str, outcome: AnalysisOutcome) {
+    if is_info_enabled() && is_text_format() && outcome != AnalysisOutcome::Complete {
+        outln!("⚠️  分析不完整 ({}): {}\n", outcome.name(), fn_name);
+    }
+}
+
+/// Output the traversal statistics of a function (`--dfs-stats`).
+/// They go with the text reports; with the machine-readable formats they go to stderr.
+pub fn report_dfs_stats(fn_name: &str, stats: &DfsStats) {
+    let mut lines = vec![
+        format!("\n=== DFS Statistics for {} ===", fn_name),
+        format!("  Total visit attempts: {}", stats.total_visit_attempts),
+        format!("  Successful visits: {}", stats.successful_visits),
+        format!("  Skipped (duplicate path): {}", stats.skipped_duplicate_path),
+        format!("  Skipped (max visits): {}", stats.skipped_max_visits),
+        format!("  Infeasible edges pruned: {}", stats.infeasible_edges),
+        format!("  Unique paths explored: {}", stats.unique_paths),
+        format!("  Unique blocks visited: {}", stats.unique_blocks),
+        format!("  Outcome: {}", stats.outcome().name()),
+    ];
+    // 计算路径爆炸因子
+    if stats.unique_blocks > 0 {
+        let explosion_factor = stats.unique_paths as f64 / stats.unique_blocks as f64;
+        lines.push(format!("  Path explosion factor: {:.2}x", explosion_factor));
+    }
+    lines.push("================================\n".to_string());
+    for line in lines {
+        if is_text_format() {
+            outln!("{}", line);
+        } else {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+/// use-after-drop 的消息；worklist 引擎中只在部分路径上被 drop 时说明是 "may"
+/// （被 drop 的可能是解引用的基础指针，与 `check_deref_dependencies` 检查的 ID 相同）
+fn use_after_drop_message(local_id: PlaceId, manager: &BindingManager) -> String {
+    let mut checked = std::iter::once(local_id).chain(local_id.deref_bases());
+    if checked.any(|id| manager.is_definitely_dropped(id)) {
+        format!("`{}` is used after it was dropped", local_id)
+    } else {
+        format!("`{}` may be used after it was dropped on some paths", local_id)
+    }
+}
+
+/// 解引用裸指针时报告 dangling pointer，其他情况报告 use-after-drop
+fn use_after_drop_kind(local_id: PlaceId, body: &Body, derefs: impl FnOnce(&mut RawDerefFinder)) -> FindingKind {
+    let local = Local::from_usize(local_id.local_index());
+    if !body.local_decls.get(local).is_some_and(|decl| decl.ty.is_raw_ptr()) {
+        return FindingKind::UseAfterDrop;
+    }
+    let mut finder = RawDerefFinder { local, found: false };
+    derefs(&mut finder);
+    if finder.found {
+        FindingKind::DanglingPointer
+    } else {
+        FindingKind::UseAfterDrop
+    }
+}
+
+/// 查找语句/terminator 中对 `local` 的解引用
+struct RawDerefFinder {
+    local: Local,
+    found: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for RawDerefFinder {
+    fn visit_place(&mut self, place: &Place<'tcx>, _context: PlaceContext, _location: Location) {
+        if place.local == self.local && place.is_indirect_first_projection() {
+            self.found = true;
+        }
+    }
+}
+
+/// dangling pointer 的消息
+fn dangling_pointer_message(local_id: PlaceId, manager: &BindingManager) -> String {
+    if manager.is_definitely_dropped(local_id) {
+        format!("raw pointer `{}` is dereferenced after its pointee was dropped", local_id)
+    } else {
+        format!("raw pointer `{}` may be dereferenced after its pointee was dropped on some paths", local_id)
+    }
+}
+
+/// 使用的值（或解引用的基础指针）没有被 drop，只是指向的栈内存离开了作用域
+fn is_use_after_scope(local_id: PlaceId, manager: &mut BindingManager) -> bool {
+    let checked: Vec<PlaceId> = std::iter::once(local_id).chain(local_id.deref_bases()).collect();
+    !checked.iter().any(|&id| manager.is_dropped(id)) && checked.iter().any(|&id| manager.is_out_of_scope(id))
+}
+
+/// use-after-scope 的消息
+fn use_after_scope_message(local_id: PlaceId, manager: &BindingManager) -> String {
+    if std::iter::once(local_id).chain(local_id.deref_bases()).any(|id| manager.is_definitely_out_of_scope(id)) {
+        format!("`{}` is used after the stack memory it points to went out of scope", local_id)
+    } else {
+        format!("`{}` may be used after the stack memory it points to went out of scope on some paths", local_id)
+    }
+}
+
+/// 选择 use-after-drop 类结果的类型和消息
+fn use_after_drop_finding(
+    local_id: PlaceId,
+    body: &Body,
+    manager: &mut BindingManager,
+    derefs: impl FnOnce(&mut RawDerefFinder),
+) -> (FindingKind, String) {
+    if is_use_after_scope(local_id, manager) {
+        return (FindingKind::UseAfterScope, use_after_scope_message(local_id, manager));
+    }
+    match use_after_drop_kind(local_id, body, derefs) {
+        FindingKind::DanglingPointer => (FindingKind::DanglingPointer, dangling_pointer_message(local_id, manager)),
+        kind => (kind, use_after_drop_message(local_id, manager)),
     }
 }
 
@@ -40,33 +351,28 @@ pub fn report_use_after_drop_stmt(
     fn_name: &str,
     stmt: &Statement,
     bb: BasicBlock,
-    local_id: &str,
+    local_id: PlaceId,
     body: &Body,
     manager: &mut BindingManager,
+    findings: &mut FindingCollector,
 ) {
-    println!("\n❌ 检测到错误: Use After Drop");
-    println!("┌{}", "─".repeat(58));
-    println!("│ 函数: {}", fn_name);
-    println!("│ 变量: {}", local_id);
-    println!("│ 位置: {:?}", stmt.source_info.span);
-    println!("│ 基本块: {:?}", bb);
-    println!("│");
-    println!("│ MIR 语句:");
-    println!("│   {:?}", stmt.kind);
-    println!("│");
-    
-    // Print variable type information
-    print_local_info(body, local_id);
-    
-    // Print binding group information
-    print_drop_path(manager, local_id, body);
-    
-    // Display basic block context
-    print_basic_block_context(body, bb);
-    
-    println!("└{}\n", "─".repeat(58));
-    
-    error!("Use after drop: {} in function {}", local_id, fn_name);
+    let location = Location { block: bb, statement_index: 0 };
+    let (kind, message) =
+        use_after_drop_finding(local_id, body, manager, |finder| finder.visit_statement(stmt, location));
+    let finding = Finding::new(
+        kind,
+        fn_name,
+        local_id,
+        message,
+        MirSite::Statement(format!("{:?}", stmt.kind)),
+        stmt.source_info.span,
+        bb,
+        body,
+        manager,
+    );
+    if findings.record(finding) {
+        error!("Use after drop: {} in function {}", local_id, fn_name);
+    }
 }
 
 /// Report use-after-drop error (Terminator version)
@@ -74,38 +380,378 @@ pub fn report_use_after_drop_term(
     fn_name: &str,
     term: &Terminator,
     bb: BasicBlock,
-    local_id: &str,
+    local_id: PlaceId,
+    body: &Body,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+) {
+    let location = Location { block: bb, statement_index: 0 };
+    let (kind, message) =
+        use_after_drop_finding(local_id, body, manager, |finder| finder.visit_terminator(term, location));
+    let finding = Finding::new(
+        kind,
+        fn_name,
+        local_id,
+        message,
+        MirSite::Terminator(format!("{:?}", term.kind)),
+        term.source_info.span,
+        bb,
+        body,
+        manager,
+    );
+    if findings.record(finding) {
+        error!("Use after drop: {} in function {}", local_id, fn_name);
+    }
+}
+
+/// Report a returned pointer (or a pointer field of the return value) whose pointee is dropped
+/// or goes out of scope before the function returns
+pub fn report_dangling_return(
+    fn_name: &str,
+    term: &Terminator,
+    bb: BasicBlock,
+    local_id: PlaceId,
+    body: &Body,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+) {
+    let dropped = manager.is_dropped(local_id);
+    let definitely = if dropped {
+        manager.is_definitely_dropped(local_id)
+    } else {
+        manager.is_definitely_out_of_scope(local_id)
+    };
+    let mut finding = Finding::new(
+        FindingKind::DanglingReturn,
+        fn_name,
+        local_id,
+        String::new(),
+        MirSite::Terminator(format!("{:?}", term.kind)),
+        term.source_info.span,
+        bb,
+        body,
+        manager,
+    );
+    let pointee = finding.drop.as_ref().map_or_else(|| "a local".to_string(), |info| format!("`{}`", info.dropped_by));
+    let reason = if dropped { "is dropped" } else { "goes out of scope" };
+    finding.message = if definitely {
+        format!("`{}` is returned but points to {}, which {} before the function returns", local_id, pointee, reason)
+    } else {
+        format!(
+            "`{}` is returned but may point to {}, which {} before the function returns on some paths",
+            local_id, pointee, reason
+        )
+    };
+    if findings.record(finding) {
+        error!("Dangling pointer {} returned from function {}", local_id, fn_name);
+    }
+}
+
+/// Report a pointer stored into static memory whose pointee goes out of scope (at the StorageDead)
+#[allow(clippy::too_many_arguments)]
+pub fn report_escape_to_global(
+    fn_name: &str,
+    stmt: &Statement,
+    bb: BasicBlock,
+    pointer: PlaceId,
+    local: PlaceId,
+    body: &Body,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+) {
+    let mut finding = Finding::new(
+        FindingKind::UseAfterScope,
+        fn_name,
+        pointer,
+        format!("`{}` stored into static memory points to `{}`, which goes out of scope here", pointer, local),
+        MirSite::Statement(format!("{:?}", stmt.kind)),
+        stmt.source_info.span,
+        bb,
+        body,
+        manager,
+    );
+    // 最后一步是离开作用域本身，不是对指针的使用
+    finding.steps.pop();
+    if findings.record(finding) {
+        error!("Pointer {} to {} escapes into static memory in function {}", pointer, local, fn_name);
+    }
+}
+
+/// Report a `from_raw` call that reclaims memory still owned by another value
+#[allow(clippy::too_many_arguments)]
+pub fn report_double_ownership(
+    fn_name: &str,
+    term: &Terminator,
+    bb: BasicBlock,
+    reclaimed: PlaceId,
+    owner: PlaceId,
+    callee: &str,
+    body: &Body,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+) {
+    let mut finding = Finding::new(
+        FindingKind::DoubleOwnership,
+        fn_name,
+        reclaimed,
+        format!("`{}` takes ownership (`{}`) of memory still owned by `{}`", reclaimed, callee, owner),
+        MirSite::Terminator(format!("{:?}", term.kind)),
+        term.source_info.span,
+        bb,
+        body,
+        manager,
+    );
+    // 最后一步是 from_raw 本身，已经由 FromRaw 事件说明
+    finding.steps.pop();
+    if findings.record(finding) {
+        error!("{} reclaims memory still owned by {} in function {}", reclaimed, owner, fn_name);
+    }
+}
+
+/// Report an owner that frees (drops or returns to the caller) memory already freed by a distinct
+/// owner of the same allocation
+#[allow(clippy::too_many_arguments)]
+pub fn report_double_free(
+    fn_name: &str,
+    term: &Terminator,
+    bb: BasicBlock,
+    local_id: PlaceId,
+    owner: PlaceId,
+    body: &Body,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+) {
+    let returned = matches!(term.kind, TerminatorKind::Return);
+    let message = if returned {
+        format!(
+            "`{}` is returned, but the memory it owns was already freed when `{}` was dropped; the caller frees it again",
+            local_id, owner
+        )
+    } else {
+        format!("`{}` frees memory that was already freed when `{}` was dropped; both own the same allocation", local_id, owner)
+    };
+    let mut finding = Finding::new(
+        FindingKind::DoubleFree,
+        fn_name,
+        local_id,
+        message,
+        MirSite::Terminator(format!("{:?}", term.kind)),
+        term.source_info.span,
+        bb,
+        body,
+        manager,
+    );
+    if let Some(last) = finding.steps.last_mut() {
+        last.message = if returned { "returned here" } else { "freed again here" }.to_string();
+    }
+    if findings.record(finding) {
+        error!("Double free of {} (also owned by {}) in function {}", local_id, owner, fn_name);
+    }
+}
+
+/// Report a value dropped again on a normal (non-unwind) path
+pub fn report_double_drop(
+    fn_name: &str,
+    term: &Terminator,
+    bb: BasicBlock,
+    local_id: PlaceId,
+    body: &Body,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+) {
+    let mut finding = Finding::new(
+        FindingKind::DoubleDrop,
+        fn_name,
+        local_id,
+        format!("`{}` is dropped again after it was already dropped", local_id),
+        MirSite::Terminator(format!("{:?}", term.kind)),
+        term.source_info.span,
+        bb,
+        body,
+        manager,
+    );
+    if let Some(last) = finding.steps.last_mut() {
+        last.message = "dropped again here".to_string();
+    }
+    if findings.record(finding) {
+        error!("Double drop of {} in function {}", local_id, fn_name);
+    }
+}
+
+/// Report memory whose ownership was released (`into_raw`, `ManuallyDrop::new`) and is still
+/// held when the function returns
+#[allow(clippy::too_many_arguments)]
+pub fn report_leak(
+    fn_name: &str,
+    term: &Terminator,
+    bb: BasicBlock,
+    local_id: PlaceId,
+    released: Span,
+    body: &Body,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+) {
+    let mut finding = Finding::new(
+        FindingKind::Leak,
+        fn_name,
+        local_id,
+        format!(
+            "`{}` holds memory whose ownership was released and is never freed: it is not reclaimed, dropped, returned or passed on",
+            local_id
+        ),
+        MirSite::Terminator(format!("{:?}", term.kind)),
+        released,
+        bb,
+        body,
+        manager,
+    );
+    if let Some(last) = finding.steps.last_mut() {
+        last.span = term.source_info.span;
+        last.message = "function returns without freeing it".to_string();
+    }
+    if findings.record(finding) {
+        error!("Memory held by {} leaks in function {}", local_id, fn_name);
+    }
+}
+
+/// Report a `ptr::write` that overwrites a value still owning memory without dropping it
+#[allow(clippy::too_many_arguments)]
+pub fn report_overwrite_leak(
+    fn_name: &str,
+    term: &Terminator,
+    bb: BasicBlock,
+    owner: PlaceId,
+    callee: &str,
+    body: &Body,
+    manager: &mut BindingManager,
+    findings: &mut FindingCollector,
+) {
+    let mut finding = Finding::new(
+        FindingKind::Leak,
+        fn_name,
+        owner,
+        format!("`{}` overwrites `{}` without dropping its old value, which leaks", callee, owner),
+        MirSite::Terminator(format!("{:?}", term.kind)),
+        term.source_info.span,
+        bb,
+        body,
+        manager,
+    );
+    if let Some(last) = finding.steps.last_mut() {
+        last.message = "overwritten here".to_string();
+    }
+    if findings.record(finding) {
+        error!("{} overwrites {} without dropping it in function {}", callee, owner, fn_name);
+    }
+}
+
+/// Report tainted data reaching a sink
+#[allow(clippy::too_many_arguments)]
+pub fn report_tainted_sink(
+    fn_name: &str,
+    term: &Terminator,
+    bb: BasicBlock,
+    local_id: PlaceId,
+    source: &str,
+    sink: &str,
+    param: usize,
     body: &Body,
     manager: &mut BindingManager,
+    findings: &mut FindingCollector,
 ) {
-    println!("\n❌ 检测到错误: Use After Drop");
-    println!("┌{}", "─".repeat(58));
-    println!("│ 函数: {}", fn_name);
-    println!("│ 变量: {}", local_id);
-    println!("│ 位置: {:?}", term.source_info.span);
-    println!("│ 基本块: {:?}", bb);
-    println!("│");
-    println!("│ MIR Terminator:");
-    println!("│   {:?}", term.kind);
-    println!("│");
+    let mut finding = Finding::new(
+        FindingKind::TaintedSink,
+        fn_name,
+        local_id,
+        format!("tainted data from `{}` reaches argument {} of `{}`", source, param, sink),
+        MirSite::Terminator(format!("{:?}", term.kind)),
+        term.source_info.span,
+        bb,
+        body,
+        manager,
+    );
+    finding.taint = Some(TaintFlow { source: source.to_string(), sink: sink.to_string(), param });
+    if findings.record(finding) {
+        error!("Tainted data from {} reaches sink {} in function {}", source, sink, fn_name);
+    }
+}
+
+/// Print the text reports of a function once it has been analyzed.
+/// Findings reached on several paths are printed once, with the number of distinct paths.
+pub fn report_function_findings(findings: &[Finding], body: &Body) {
+    if !is_text_format() {
+        return;
+    }
+    for finding in findings {
+        print_finding(finding, body);
+    }
+}
+
+/// Print one finding in the text format
+fn print_finding(finding: &Finding, body: &Body) {
+    let title = match finding.kind {
+        FindingKind::UseAfterDrop => "Use After Drop",
+        FindingKind::DanglingPointer => "Dangling Pointer Use",
+        FindingKind::UseAfterScope => "Use After Scope",
+        FindingKind::DanglingReturn => "Dangling Pointer Returned",
+        FindingKind::DoubleOwnership => "Double Ownership",
+        FindingKind::DoubleFree => "Double Free",
+        FindingKind::DoubleDrop => "Double Drop",
+        FindingKind::Leak => "Memory Leak",
+        FindingKind::TaintedSink => "Tainted Data Reaches Sink",
+    };
+    outln!("\n❌ 检测到错误: {}", title);
+    outln!("┌{}", "─".repeat(58));
+    outln!("│ 函数: {}", finding.function);
+    outln!("│ 变量: {}", finding.local);
+    outln!("│ 位置: {:?}", finding.span);
+    outln!("│ 基本块: {:?}", finding.bb);
+    outln!("│ 路径数: {}", finding.path_count);
+    if finding.unwind {
+        outln!("│ ⚡ 只在 panic 时的 unwind 路径（cleanup block）上");
+    }
+    outln!("│");
+    if let Some(taint) = &finding.taint {
+        outln!("│ ☣️  污点来源: {}", taint.source);
+        outln!("│ 🎯 Sink: {} (参数 {})", taint.sink, taint.param);
+        outln!("│");
+    }
+    match &finding.mir {
+        MirSite::Statement(text) => outln!("│ MIR 语句:\n│   {}", text),
+        MirSite::Terminator(text) => outln!("│ MIR Terminator:\n│   {}", text),
+    }
+    outln!("│");
     
     // Print variable type information
-    print_local_info(body, local_id);
+    print_local_info(body, finding.base);
     
     // Print binding group information
-    print_drop_path(manager, local_id, body);
+    if finding.kind != FindingKind::TaintedSink {
+        print_drop_path(finding, body);
+    }
     
-    // Display basic block context
-    print_basic_block_context(body, bb);
+    // Print the events on the path that lead to the error
+    outln!("│");
+    outln!("│ 🧭 错误步骤: {}", finding.trace());
+    for (idx, step) in finding.steps.iter().enumerate() {
+        outln!("│   {}. {} ({:?}, {:?})", idx + 1, step.message, step.bb, step.span);
+    }
+    if !finding.conditions.is_empty() {
+        outln!("│ 🔀 分支条件:");
+        for step in &finding.conditions {
+            outln!("│   - {} ({:?}, {:?})", step.message, step.bb, step.span);
+        }
+    }
     
-    println!("└{}\n", "─".repeat(58));
+    // Display basic block context
+    print_basic_block_context(body, finding.bb);
     
-    error!("Use after drop: {} in function {}", local_id, fn_name);
+    outln!("└{}\n", "─".repeat(58));
 }
 
 /// Display basic block context information
 fn print_basic_block_context(body: &Body, bb: BasicBlock) {
-    println!("│ 基本块上下文 [{:?}]:", bb);
+    outln!("│ 基本块上下文 [{:?}]:", bb);
     
     let block = &body.basic_blocks[bb];
     
@@ -114,69 +760,66 @@ fn print_basic_block_context(body: &Body, bb: BasicBlock) {
     let start = if stmt_count > 3 { stmt_count - 3 } else { 0 };
     
     for (idx, stmt) in block.statements.iter().enumerate().skip(start) {
-        println!("│     [{}] {:?}", idx, stmt.kind);
+        outln!("│     [{}] {:?}", idx, stmt.kind);
     }
     
     // Display terminator
     if let Some(ref term) = block.terminator {
-        println!("│     [T] {:?}", term.kind);
+        outln!("│     [T] {:?}", term.kind);
     }
 }
 
 /// Print variable definition information
-fn print_local_info(body: &Body, local_id: &str) {
-    if let Ok(local_idx) = local_id.trim_start_matches('_').parse::<usize>() {
-        let local = Local::from_usize(local_idx);
-        if let Some(local_decl) = body.local_decls.get(local) {
-            println!("│ 变量类型: {:?}", local_decl.ty);
-            println!("│ 可变性: {:?}", local_decl.mutability);
-        }
+fn print_local_info(body: &Body, local: Local) {
+    if let Some(local_decl) = body.local_decls.get(local) {
+        outln!("│ 变量类型: {:?}", local_decl.ty);
+        outln!("│ 可变性: {:?}", local_decl.mutability);
     }
 }
 
 /// Display variable's drop path tracking
-fn print_drop_path(manager: &mut BindingManager, local_id: &str, body: &Body) {
-    println!("│");
-    println!("│ 📊 变量状态追踪:");
-    println!("│   当前状态: dropped={}", manager.is_dropped(local_id));
+fn print_drop_path(finding: &Finding, body: &Body) {
+    outln!("│");
+    outln!("│ 📊 变量状态追踪:");
+    outln!("│   当前状态: dropped={}", finding.is_dropped);
     
-    if let Some((root_id, members)) = manager.find_group(local_id) {
-        println!("│   绑定组根: {}", root_id);
-        println!("│   组内成员: {:?}", members);
+    if let Some(root_id) = &finding.group_root {
+        outln!("│   绑定组根: {}", root_id);
+        outln!("│   组内成员: {:?}", finding.group_members);
         
         // 显示drop位置信息
-        if let Some(drop_info) = crate::state::LocalState::get_drop_info(&root_id, &manager.states) {
-            println!("│");
-            println!("│ 🚨 Drop位置追踪:");
-            print_drop_info(&drop_info, body);
+        if let Some(drop_info) = &finding.drop {
+            outln!("│");
+            outln!("│ 🚨 Drop位置追踪:");
+            print_drop_info(drop_info, body);
         }
     }
 }
 
 /// 打印drop位置的详细信息
 fn print_drop_info(drop_info: &crate::state::DropInfo, body: &Body) {
-    println!("│   被Drop变量: {}", drop_info.dropped_by);
-    println!("│   所在函数: {}", drop_info.function_name);
+    outln!("│   被Drop变量: {}", drop_info.dropped_by);
+    outln!("│   所在函数: {}", drop_info.function_name);
     
     match &drop_info.location {
         crate::state::DropLocation::Terminator { bb, span, kind } => {
-            println!("│   Drop类型: {:?}", kind);
-            println!("│   基本块: {:?}", bb);
-            println!("│   源码位置: {:?}", span);
+            outln!("│   Drop类型: {:?}", kind);
+            outln!("│   基本块: {:?}", bb);
+            outln!("│   源码位置: {:?}", span);
             
             // 显示该BasicBlock的上下文（可选）
             if let Some(block) = body.basic_blocks.get(*bb) {
-                println!("│   Drop上下文:");
+                outln!("│   Drop上下文:");
                 if let Some(ref term) = block.terminator {
-                    println!("│     {:?}", term.kind);
+                    outln!("│     {:?}", term.kind);
                 }
             }
         }
         crate::state::DropLocation::Statement { bb, span, stmt_index } => {
-            println!("│   Drop类型: Statement");
-            println!("│   基本块: {:?}", bb);
-            println!("│   语句索引: {}", stmt_index);
-            println!("│   源码位置: {:?}", span);
+            outln!("│   Drop类型: StorageDead");
+            outln!("│   基本块: {:?}", bb);
+            outln!("│   语句索引: {}", stmt_index);
+            outln!("│   源码位置: {:?}", span);
         }
     }
 }
@@ -0,0 +1,205 @@
+//! SARIF 2.1.0 output.
+//! Every driver process builds one SARIF log for its crate. When running under
+//! `cargo taint-ana`, the logs are written to the directory in `TAINT_ANA_SARIF_DIR`
+//! (`options::SARIF_DIR_ENV`) and merged
+//! into a single file after `cargo build` finishes (see `src/bin/cargo-taint-ana.rs`).
+
+use std::path::Path;
+
+use rustc_middle::ty::TyCtxt;
+use serde_json::{json, Value};
+
+use crate::finding::{resolve_span, Finding, FindingKind, SourceRegion};
+use crate::report::IncompleteCoverage;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// 构建包含所有检测结果的 SARIF 日志；覆盖不完整的函数记录为 `toolExecutionNotifications`
+pub fn to_sarif_log(tcx: TyCtxt<'_>, findings: &[Finding], incomplete: &[IncompleteCoverage]) -> Value {
+    let rules: Vec<Value> = FindingKind::ALL
+        .iter()
+        .map(|kind| {
+            json!({
+                "id": kind.rule_id(),
+                "shortDescription": { "text": kind.description() },
+                "defaultConfiguration": { "level": "error" },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = findings.iter().map(|finding| to_result(tcx, finding)).collect();
+    let notifications: Vec<Value> = incomplete.iter().map(to_notification).collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "taint-ana",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "invocations": [{
+                "executionSuccessful": true,
+                "toolExecutionNotifications": notifications,
+            }],
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }]
+    })
+}
+
+/// 覆盖不完整的函数对应的 SARIF `notification`
+fn to_notification(entry: &IncompleteCoverage) -> Value {
+    json!({
+        "level": "warning",
+        "descriptor": { "id": "incomplete-coverage" },
+        "message": { "text": format!("analysis of `{}` {}: {}", entry.function, entry.outcome.name(), entry.reason) },
+        "locations": [{ "logicalLocations": [{ "fullyQualifiedName": entry.function, "kind": "function" }] }],
+    })
+}
+
+/// 一个检测结果对应的 SARIF `result`
+fn to_result(tcx: TyCtxt<'_>, finding: &Finding) -> Value {
+    let rule_index = FindingKind::ALL.iter().position(|kind| *kind == finding.kind);
+    let mut location = resolve_span(tcx, finding.span)
+        .map(|region| physical_location(&region))
+        .unwrap_or_else(|| json!({}));
+    location["logicalLocations"] = json!([{ "fullyQualifiedName": finding.function, "kind": "function" }]);
+
+    let mut result = json!({
+        "ruleId": finding.kind.rule_id(),
+        "ruleIndex": rule_index,
+        // 只在 panic 路径上出现的错误降为 warning
+        "level": if finding.unwind { "warning" } else { "error" },
+        "message": { "text": format!("{} ({})", finding.message, finding.trace()) },
+        "locations": [location],
+        "properties": {
+            "local": finding.local,
+            "basicBlock": format!("{:?}", finding.bb),
+            "pathCount": finding.path_count,
+            "conditions": finding.conditions.iter().map(|step| step.message.as_str()).collect::<Vec<_>>(),
+            "unwind": finding.unwind,
+        },
+    });
+
+    // drop 位置
+    if let Some(region) = finding.drop_span().and_then(|span| resolve_span(tcx, span)) {
+        let mut related = physical_location(&region);
+        related["id"] = json!(0);
+        related["message"] = json!({ "text": finding.drop_label() });
+        result["relatedLocations"] = json!([related]);
+    }
+
+    // 两个 codeFlow：导致错误的步骤（move/drop/...），以及 DFS 路径（每个 BasicBlock 用其 terminator 的位置表示）
+    let trace: Vec<Value> = finding
+        .steps
+        .iter()
+        .filter_map(|step| {
+            let mut location = thread_flow_location(tcx, step.span, step.message.clone())?;
+            location["properties"] = json!({ "basicBlock": format!("{:?}", step.bb) });
+            Some(location)
+        })
+        .collect();
+    let path: Vec<Value> = finding
+        .path
+        .iter()
+        .filter_map(|step| thread_flow_location(tcx, step.span, format!("{:?}", step.bb)))
+        .collect();
+    let code_flows: Vec<Value> = [("steps leading to the error", trace), ("basic blocks on the path", path)]
+        .into_iter()
+        .filter(|(_, locations)| !locations.is_empty())
+        .map(|(message, locations)| json!({ "message": { "text": message }, "threadFlows": [{ "locations": locations }] }))
+        .collect();
+    if !code_flows.is_empty() {
+        result["codeFlows"] = json!(code_flows);
+    }
+    result
+}
+
+/// SARIF `threadFlowLocation`
+fn thread_flow_location(tcx: TyCtxt<'_>, span: rustc_span::Span, message: String) -> Option<Value> {
+    let mut location = physical_location(&resolve_span(tcx, span)?);
+    location["message"] = json!({ "text": message });
+    Some(json!({ "location": location }))
+}
+
+/// SARIF `location`；相对路径以 `%SRCROOT%`（cargo 工作区根目录）为基准
+fn physical_location(region: &SourceRegion) -> Value {
+    let artifact = if Path::new(&region.file).is_absolute() {
+        json!({ "uri": file_uri(&region.file) })
+    } else {
+        json!({ "uri": encode_uri_path(&region.file), "uriBaseId": "%SRCROOT%" })
+    };
+    json!({
+        "physicalLocation": {
+            "artifactLocation": artifact,
+            "region": {
+                "startLine": region.start_line,
+                "startColumn": region.start_column,
+                "endLine": region.end_line,
+                "endColumn": region.end_column,
+            }
+        }
+    })
+}
+
+/// 绝对路径的 `file` URI：`/src/a b.rs` → `file:///src/a%20b.rs`，`C:\src\a.rs` → `file:///C:/src/a.rs`，
+/// UNC 路径 `\\server\share\a.rs` → `file://server/share/a.rs`
+fn file_uri(path: &str) -> String {
+    // Windows 的 verbatim 前缀（`\\?\C:\...`）不属于路径本身
+    let path = encode_uri_path(path.strip_prefix(r"\\?\").unwrap_or(path));
+    if path.starts_with("//") {
+        format!("file:{}", path)
+    } else if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        format!("file:///{}", path)
+    }
+}
+
+/// 把路径转成 URI 路径：`\` 换成 `/`，URI 中不能直接出现的字节（空格、`#`、`%`、非 ASCII 等）百分号编码
+fn encode_uri_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.replace('\\', "/").bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试1: 绝对路径（Unix、Windows 盘符、UNC）转成 file URI，特殊字符被编码
+    #[test]
+    fn test_file_uri() {
+        assert_eq!(file_uri("/home/me/my crate/src/lib.rs"), "file:///home/me/my%20crate/src/lib.rs");
+        assert_eq!(file_uri("C:\\Users\\me\\src\\lib.rs"), "file:///C:/Users/me/src/lib.rs");
+        assert_eq!(file_uri(r"\\?\C:\src\lib.rs"), "file:///C:/src/lib.rs");
+        assert_eq!(file_uri("\\\\server\\share\\lib.rs"), "file://server/share/lib.rs");
+        assert_eq!(file_uri("/src/a#1%.rs"), "file:///src/a%231%25.rs");
+        assert_eq!(file_uri("/src/模块.rs"), "file:///src/%E6%A8%A1%E5%9D%97.rs");
+    }
+
+    /// 测试2: 相对路径相对 `%SRCROOT%`
+    #[test]
+    fn test_relative_location() {
+        let region = SourceRegion {
+            file: "src\\my mod.rs".to_string(),
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 2,
+        };
+        let artifact = &physical_location(&region)["physicalLocation"]["artifactLocation"];
+        assert_eq!(artifact["uri"], "src/my%20mod.rs");
+        assert_eq!(artifact["uriBaseId"], "%SRCROOT%");
+    }
+}
@@ -0,0 +1,296 @@
+//! Per-function drop/escape summaries for interprocedural analysis.
+//! Functions are analyzed bottom-up over the `CallGraph`; each analysis
+//! produces a summary that is applied at the call sites of its callers.
+
+use std::collections::{BTreeSet, HashMap};
+
+use rustc_span::def_id::DefId;
+use rustc_middle::mir::Body;
+use rustc_middle::ty::{GenericArgsRef, Instance, TyCtxt, TyKind, TypeVisitableExt};
+
+use crate::place::PlaceId;
+use crate::state::{BindingManager, LocalState};
+
+/// 单个函数的 drop/escape 摘要
+///
+/// 参数下标从 1 开始，与 MIR 中的 `_1.._n` 一一对应
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FnSummary {
+    /// 在所有返回路径上都被 drop（或被 move 给会 drop 它的被调函数）的参数
+    pub dropped_params: BTreeSet<usize>,
+    /// 值本身流入返回值的参数（所有权随返回值转移给调用者）
+    pub escaped_params: BTreeSet<usize>,
+    /// 返回值是指向该参数内部的引用/裸指针（返回值别名该参数）
+    pub aliased_params: BTreeSet<usize>,
+    /// 返回值在某条路径上被污点源污染时，记录污点来源
+    pub tainted_return: Option<String>,
+}
+
+impl FnSummary {
+    /// 摘要是否没有任何调用点效果
+    pub fn is_empty(&self) -> bool {
+        self.dropped_params.is_empty()
+            && self.escaped_params.is_empty()
+            && self.aliased_params.is_empty()
+            && self.tainted_return.is_none()
+    }
+
+    /// 合并另一个实例化的摘要：drop 取交集，escape/alias 取并集
+    pub fn merge(&mut self, other: &FnSummary) {
+        self.dropped_params = self.dropped_params.intersection(&other.dropped_params).copied().collect();
+        self.escaped_params.extend(other.escaped_params.iter().copied());
+        self.aliased_params.extend(other.aliased_params.iter().copied());
+        if self.tainted_return.is_none() {
+            self.tainted_return = other.tainted_return.clone();
+        }
+    }
+}
+
+/// 所有已分析函数的摘要
+///
+/// 具体实例化（如 `foo::<i32>`）按 Instance 索引；只做了多态分析的泛型函数
+/// 按 DefId 索引，作为无法精确匹配实例时的回退
+#[derive(Debug, Default)]
+pub struct SummaryMap<'tcx> {
+    by_instance: HashMap<Instance<'tcx>, FnSummary>,
+    by_def_id: HashMap<DefId, FnSummary>,
+}
+
+impl<'tcx> SummaryMap<'tcx> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一个实例的摘要
+    ///
+    /// 具体实例的摘要同时合并到 DefId 级别，供多态调用点（如 `foo::<T>`）回退使用
+    pub fn insert(&mut self, instance: Instance<'tcx>, summary: FnSummary) {
+        if instance.args.has_param() {
+            self.by_def_id.insert(instance.def_id(), summary);
+            return;
+        }
+        self.by_def_id
+            .entry(instance.def_id())
+            .and_modify(|merged| merged.merge(&summary))
+            .or_insert_with(|| summary.clone());
+        self.by_instance.insert(instance, summary);
+    }
+
+    /// 查找调用点的摘要：优先精确实例，否则回退到多态摘要
+    pub fn get(&self, callee: DefId, instance: Option<Instance<'tcx>>) -> Option<&FnSummary> {
+        instance
+            .and_then(|instance| self.by_instance.get(&instance))
+            .or_else(|| self.by_def_id.get(&callee))
+    }
+}
+
+/// 在 DFS 过程中收集每个 Return 处的状态，最终合成 FnSummary
+pub struct SummaryBuilder {
+    arg_count: usize,
+    /// 返回值是否是引用/裸指针（用于区分 escape 和 alias）
+    return_is_pointer: bool,
+    /// 每个参数是否是引用/裸指针
+    param_is_pointer: Vec<bool>,
+    /// 已经记录的 Return 数量
+    returns_seen: usize,
+    /// 在所有已记录的 Return 处都被 drop 的参数
+    dropped: BTreeSet<usize>,
+    /// 在任一 Return 处与 `_0` 同组的参数
+    bound_to_return: BTreeSet<usize>,
+    /// 在任一 Return 处 `_0` 的污点来源
+    tainted_return: Option<String>,
+}
+
+impl SummaryBuilder {
+    pub fn new(arg_count: usize, return_is_pointer: bool, param_is_pointer: Vec<bool>) -> Self {
+        Self {
+            arg_count,
+            return_is_pointer,
+            param_is_pointer,
+            returns_seen: 0,
+            dropped: BTreeSet::new(),
+            bound_to_return: BTreeSet::new(),
+            tainted_return: None,
+        }
+    }
+
+    /// 根据 MIR body 的签名创建 builder
+    pub fn for_body(body: &Body<'_>) -> Self {
+        let is_pointer = |ty: rustc_middle::ty::Ty<'_>| matches!(ty.kind(), TyKind::Ref(..) | TyKind::RawPtr(..));
+        let return_is_pointer = is_pointer(body.return_ty());
+        let param_is_pointer = body
+            .args_iter()
+            .map(|local| is_pointer(body.local_decls[local].ty))
+            .collect();
+        Self::new(body.arg_count, return_is_pointer, param_is_pointer)
+    }
+
+    /// 在 Return terminator 处记录当前路径的状态
+    pub fn record_return(&mut self, manager: &mut BindingManager) {
+        let return_root = LocalState::find_root_from_id(PlaceId::local(0), &manager.states).map(|(root, _)| root);
+
+        let mut dropped_here = BTreeSet::new();
+        for idx in 1..=self.arg_count {
+            let param_id = PlaceId::local(idx);
+            if manager.is_definitely_dropped(param_id) {
+                dropped_here.insert(idx);
+            }
+            let param_root = LocalState::find_root_from_id(param_id, &manager.states).map(|(root, _)| root);
+            if param_root.is_some() && param_root == return_root {
+                self.bound_to_return.insert(idx);
+            }
+        }
+
+        // 污点取所有返回路径的并集（may-taint）
+        if self.tainted_return.is_none() {
+            self.tainted_return = manager.taint_of(PlaceId::local(0));
+        }
+
+        // dropped 取所有返回路径的交集（must-drop），避免条件释放在调用者处产生误报
+        if self.returns_seen == 0 {
+            self.dropped = dropped_here;
+        } else {
+            self.dropped = self.dropped.intersection(&dropped_here).copied().collect();
+        }
+        self.returns_seen += 1;
+    }
+
+    pub fn finish(self) -> FnSummary {
+        let mut summary = FnSummary {
+            dropped_params: self.dropped,
+            tainted_return: self.tainted_return,
+            ..Default::default()
+        };
+        for idx in self.bound_to_return {
+            let param_is_pointer = self.param_is_pointer.get(idx - 1).copied().unwrap_or(false);
+            if self.return_is_pointer || param_is_pointer {
+                summary.aliased_params.insert(idx);
+            } else {
+                summary.escaped_params.insert(idx);
+            }
+        }
+        summary
+    }
+}
+
+/// 解析调用点的目标实例
+///
+/// 对 trait 方法尝试解析到具体 impl；在多态函数体中可能无法解析，此时返回 None
+pub fn resolve_callee<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    callee: DefId,
+    args: GenericArgsRef<'tcx>,
+) -> Option<Instance<'tcx>> {
+    Instance::try_resolve(tcx, body.typing_env(tcx), callee, args).ok().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn l(local: usize) -> PlaceId {
+        PlaceId::local(local)
+    }
+
+    fn manager_with_locals(count: usize) -> BindingManager {
+        let mut manager = BindingManager::new("callee");
+        for i in 0..count {
+            manager.register(PlaceId::local(i), None);
+        }
+        manager
+    }
+
+    /// 测试1: 在所有返回路径都被 drop 的参数才会进入 dropped_params
+    #[test]
+    fn test_dropped_params_is_intersection() {
+        let mut builder = SummaryBuilder::new(2, false, vec![false, false]);
+
+        // 路径 1: drop _1 和 _2
+        let mut path1 = manager_with_locals(3);
+        path1.idrop_group(l(1));
+        path1.idrop_group(l(2));
+        builder.record_return(&mut path1);
+
+        // 路径 2: 只 drop _1
+        let mut path2 = manager_with_locals(3);
+        path2.idrop_group(l(1));
+        builder.record_return(&mut path2);
+
+        let summary = builder.finish();
+        assert_eq!(summary.dropped_params, BTreeSet::from([1]));
+    }
+
+    /// 测试2: 与 _0 同组的按值参数视为 escape
+    #[test]
+    fn test_escaped_param() {
+        let mut builder = SummaryBuilder::new(1, false, vec![false]);
+        let mut manager = manager_with_locals(2);
+        manager.bind(l(1), l(0)).unwrap();
+        builder.record_return(&mut manager);
+
+        let summary = builder.finish();
+        assert_eq!(summary.escaped_params, BTreeSet::from([1]));
+        assert!(summary.aliased_params.is_empty());
+        assert!(summary.dropped_params.is_empty());
+    }
+
+    /// 测试3: 返回裸指针时与 _0 同组的参数视为 alias
+    #[test]
+    fn test_aliased_param() {
+        let mut builder = SummaryBuilder::new(2, true, vec![true, false]);
+        let mut manager = manager_with_locals(3);
+        manager.register(PlaceId::local(3), None);
+        manager.bind(l(3), l(1)).unwrap();
+        manager.bind(l(0), l(3)).unwrap();
+        builder.record_return(&mut manager);
+
+        let summary = builder.finish();
+        assert_eq!(summary.aliased_params, BTreeSet::from([1]));
+        assert!(summary.escaped_params.is_empty());
+    }
+
+    /// 测试4: 合并多个实例化的摘要
+    #[test]
+    fn test_merge_summaries() {
+        let mut merged = FnSummary {
+            dropped_params: BTreeSet::from([1, 2]),
+            escaped_params: BTreeSet::from([1]),
+            ..Default::default()
+        };
+        let other = FnSummary {
+            dropped_params: BTreeSet::from([2]),
+            aliased_params: BTreeSet::from([3]),
+            ..Default::default()
+        };
+        merged.merge(&other);
+
+        assert_eq!(merged.dropped_params, BTreeSet::from([2]));
+        assert_eq!(merged.escaped_params, BTreeSet::from([1]));
+        assert_eq!(merged.aliased_params, BTreeSet::from([3]));
+    }
+
+    /// 测试5: 任一返回路径上 _0 被污染，摘要就记录污点来源
+    #[test]
+    fn test_tainted_return() {
+        let mut builder = SummaryBuilder::new(0, false, vec![]);
+
+        let mut clean = manager_with_locals(1);
+        builder.record_return(&mut clean);
+
+        let mut tainted = manager_with_locals(1);
+        tainted.taint(l(0), "std::env::var".to_string());
+        builder.record_return(&mut tainted);
+
+        let summary = builder.finish();
+        assert_eq!(summary.tainted_return, Some("std::env::var".to_string()));
+        assert!(!summary.is_empty());
+    }
+
+    /// 测试6: 没有 Return 的函数摘要为空
+    #[test]
+    fn test_no_return_is_empty() {
+        let builder = SummaryBuilder::new(1, false, vec![false]);
+        assert!(builder.finish().is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+//! Source/sink/sanitizer rules for taint tracking.
+//! Taint labels are stored on the union-find group root (`LocalState::root`),
+//! so they follow the same move/borrow bindings as the drop state.
+
+use std::sync::OnceLock;
+
+/// 污点源把哪个位置标记为被污染
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaintTarget {
+    /// 函数返回值（如 `std::env::var`）
+    Return,
+    /// 第 N 个实参（从 0 开始）指向的缓冲区（如 `Read::read(&mut self, buf)` 的 buf）
+    Arg(usize),
+}
+
+/// 污点源规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceRule {
+    pub pattern: String,
+    pub target: TaintTarget,
+}
+
+/// 污点汇聚点规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SinkRule {
+    pub pattern: String,
+    /// 需要检查的实参下标（从 0 开始）；为空表示检查所有实参
+    pub params: Vec<usize>,
+}
+
+/// 所有的 source/sink/sanitizer 规则
+///
+/// 规则按函数路径（`tcx.def_path_str`）匹配：路径等于规则，或以 `::规则` 结尾
+#[derive(Debug, Clone, Default)]
+pub struct TaintRules {
+    pub sources: Vec<SourceRule>,
+    pub sinks: Vec<SinkRule>,
+    pub sanitizers: Vec<String>,
+}
+
+impl TaintRules {
+    /// 内置的默认规则
+    pub fn builtin() -> Self {
+        let source = |pattern: &str, target: TaintTarget| SourceRule { pattern: pattern.to_string(), target };
+        let sink = |pattern: &str, params: &[usize]| SinkRule { pattern: pattern.to_string(), params: params.to_vec() };
+
+        Self {
+            sources: vec![
+                // 环境变量与命令行参数
+                source("std::env::var", TaintTarget::Return),
+                source("std::env::var_os", TaintTarget::Return),
+                source("std::env::args", TaintTarget::Return),
+                source("std::env::args_os", TaintTarget::Return),
+                // Read/BufRead（包括 File、TcpStream、Stdin 等的实现）
+                source("std::io::Read::read", TaintTarget::Arg(1)),
+                source("std::io::Read::read_exact", TaintTarget::Arg(1)),
+                source("std::io::Read::read_to_end", TaintTarget::Arg(1)),
+                source("std::io::Read::read_to_string", TaintTarget::Arg(1)),
+                source("std::io::BufRead::read_line", TaintTarget::Arg(1)),
+                source("std::io::Stdin::read_line", TaintTarget::Arg(1)),
+                // 网络
+                source("std::net::TcpStream::peek", TaintTarget::Arg(1)),
+                source("std::net::UdpSocket::recv", TaintTarget::Arg(1)),
+                source("std::net::UdpSocket::recv_from", TaintTarget::Arg(1)),
+            ],
+            sinks: vec![
+                // 命令执行
+                sink("std::process::Command::new", &[0]),
+                sink("std::process::Command::arg", &[1]),
+                sink("std::process::Command::args", &[1]),
+                sink("libc::system", &[0]),
+                sink("libc::execv", &[0, 1]),
+                sink("libc::execvp", &[0, 1]),
+                // 文件路径
+                sink("std::fs::File::open", &[0]),
+                sink("std::fs::File::create", &[0]),
+                sink("std::fs::read", &[0]),
+                sink("std::fs::read_to_string", &[0]),
+                sink("std::fs::write", &[0]),
+                sink("std::fs::remove_file", &[0]),
+                sink("std::fs::remove_dir_all", &[0]),
+            ],
+            sanitizers: vec![
+                "shell_escape::escape".to_string(),
+                "shlex::quote".to_string(),
+                "shlex::try_quote".to_string(),
+            ],
+        }
+    }
+
+    /// 默认规则 + taint-ana.toml `[taint]` + 环境变量中用户声明的规则
+    ///
+    /// 每条规则的格式为 `path` 或 `path#N`（第 N 个实参，从 0 开始）：
+    /// - `TAINT_ANA_SOURCES`：逗号分隔，`path` 污染返回值，`path#N` 污染第 N 个实参
+    /// - `TAINT_ANA_SINKS`：逗号分隔，`path` 检查所有实参，`path#N` 只检查第 N 个实参
+    /// - `TAINT_ANA_SANITIZERS`：逗号分隔的函数路径
+    pub fn load() -> Self {
+        let mut rules = Self::builtin();
+        let config = &crate::config::get().taint;
+        let specs = |configured: &[String], var: &str| -> Vec<(String, Option<usize>)> {
+            let from_env: Vec<String> = std::env::var(var)
+                .map(|value| value.split(',').filter(|s| !s.trim().is_empty()).map(str::to_string).collect())
+                .unwrap_or_default();
+            configured
+                .iter()
+                .chain(from_env.iter())
+                .filter_map(|spec| match parse_rule_spec(spec) {
+                    Ok(rule) => Some(rule),
+                    Err(e) => {
+                        eprintln!("⚠️  Warning: ignoring taint rule in {}: {}", var, e);
+                        None
+                    }
+                })
+                .collect()
+        };
+
+        for (pattern, arg) in specs(&config.sources, "TAINT_ANA_SOURCES") {
+            let target = arg.map_or(TaintTarget::Return, TaintTarget::Arg);
+            rules.sources.push(SourceRule { pattern, target });
+        }
+        for (pattern, arg) in specs(&config.sinks, "TAINT_ANA_SINKS") {
+            rules.sinks.push(SinkRule { pattern, params: arg.into_iter().collect() });
+        }
+        for (pattern, _) in specs(&config.sanitizers, "TAINT_ANA_SANITIZERS") {
+            rules.sanitizers.push(pattern);
+        }
+        rules
+    }
+
+    pub fn source(&self, path: &str) -> Option<&SourceRule> {
+        self.sources.iter().find(|rule| path_matches(path, &rule.pattern))
+    }
+
+    pub fn sink(&self, path: &str) -> Option<&SinkRule> {
+        self.sinks.iter().find(|rule| path_matches(path, &rule.pattern))
+    }
+
+    pub fn is_sanitizer(&self, path: &str) -> bool {
+        self.sanitizers.iter().any(|pattern| path_matches(path, pattern))
+    }
+}
+
+/// 全局污点规则（懒加载，只读取一次）
+static TAINT_RULES: OnceLock<TaintRules> = OnceLock::new();
+
+pub fn get_taint_rules() -> &'static TaintRules {
+    TAINT_RULES.get_or_init(TaintRules::load)
+}
+
+/// 函数路径是否匹配规则：完全相等，或者在 `::` 边界上以规则结尾
+///
+/// 例如 `libc::system` 匹配规则 `system`，但 `my_system` 不匹配
+fn path_matches(path: &str, pattern: &str) -> bool {
+    path == pattern
+        || path
+            .strip_suffix(pattern)
+            .is_some_and(|prefix| prefix.ends_with("::"))
+}
+
+/// 解析一条规则：`path` 或 `path#N`
+pub fn parse_rule_spec(spec: &str) -> Result<(String, Option<usize>), String> {
+    let spec = spec.trim();
+    let (path, idx) = match spec.split_once('#') {
+        Some((path, idx)) => {
+            let idx = idx
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid taint rule `{}`: argument index must be a number", spec))?;
+            (path.trim(), Some(idx))
+        }
+        None => (spec, None),
+    };
+    if path.is_empty() {
+        return Err(format!("invalid taint rule `{}`: empty function path", spec));
+    }
+    Ok((path.to_string(), idx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试1: 路径匹配只在 `::` 边界上生效
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches("std::env::var", "std::env::var"));
+        assert!(path_matches("libc::system", "system"));
+        assert!(path_matches("std::process::Command::arg", "Command::arg"));
+        assert!(!path_matches("my_crate::my_system", "system"));
+        assert!(!path_matches("std::env::var_os", "std::env::var"));
+    }
+
+    /// 测试2: 解析用户声明的规则
+    #[test]
+    fn test_parse_rule_spec() {
+        assert_eq!(parse_rule_spec(" my::source "), Ok(("my::source".to_string(), None)));
+        assert_eq!(parse_rule_spec("my::read#1"), Ok(("my::read".to_string(), Some(1))));
+        assert!(parse_rule_spec("my::read#x").is_err());
+        assert!(parse_rule_spec("").is_err());
+        assert!(parse_rule_spec("#1").is_err());
+    }
+
+    /// 测试3: 内置规则查找
+    #[test]
+    fn test_builtin_rules() {
+        let rules = TaintRules::builtin();
+        assert_eq!(rules.source("std::env::var").map(|r| &r.target), Some(&TaintTarget::Return));
+        assert_eq!(rules.source("std::io::Read::read").map(|r| &r.target), Some(&TaintTarget::Arg(1)));
+        assert_eq!(rules.sink("std::process::Command::arg").map(|r| r.params.clone()), Some(vec![1]));
+        assert!(rules.is_sanitizer("shell_escape::escape"));
+        assert!(rules.source("std::env::vars").is_none());
+    }
+}
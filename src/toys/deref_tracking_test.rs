@@ -34,25 +34,24 @@ fn test_pointer_vs_deref() {
     }
 }
 
-/// 测试3：依赖检查 - drop 指针后解引用
+/// 测试3：依赖检查 - drop 引用后解引用指针
 /// 
-/// 期望：检测到 `drop(ptr)` 后使用 `*ptr` 的错误
+/// 期望：不报错。`drop(r)` 只 drop 了引用，`v` 仍然存活，`*ptr` 是合法的
 /// 
-/// 注意：这个测试比较特殊，因为 MIR 中通常不会直接 drop 原始指针。
-/// 但对于引用转指针的情况，这个测试验证依赖检查是否工作。
+/// 注意：`mem::drop` 按 DefId 识别，参数是引用、裸指针这类不需要 drop 的值时
+/// 不会把它们指向的值标记为 dropped。
 #[allow(unused)]
 fn test_deref_dependency() {
     let mut v = vec![1, 2, 3];
     let r = &mut v;
     let ptr = r as *mut Vec<i32>;
     
-    // 在 MIR 中，drop(r) 会标记 r 为 dropped
-    // 由于 ptr 绑定到 r，ptr 也会被标记为 dropped
+    // drop(r) 只 drop 了引用本身
     drop(r);
     
     unsafe {
-        // 使用 *ptr 时，依赖检查会发现 ptr 已 dropped
-        let x = ptr::read(ptr); // ❌ 应该报错：指针已 drop，无法解引用
+        // v 没有被 drop，通过 ptr 读取是合法的
+        let x = ptr::read(ptr); // ✓ 不应该报错
     }
 }
 
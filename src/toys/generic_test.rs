@@ -0,0 +1,52 @@
+//! 集成测试：验证泛型函数的分析
+//!
+//! 泛型函数按本 crate 中实际收集到的实例化分别分析；没有实例化的泛型函数按多态 body 分析
+
+struct Stack<T> {
+    items: Vec<T>,
+}
+
+impl<T> Stack<T> {
+    fn new() -> Self {
+        Stack { items: Vec::new() }
+    }
+
+    fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    /// 返回指向已释放缓冲区的指针
+    fn dangling_top(self) -> *const T {
+        let items = self.items;
+        let ptr = items.as_ptr();
+        drop_vec(items);
+        ptr
+    }
+}
+
+fn drop_vec<T>(v: Vec<T>) {
+    let _v = v;
+}
+
+/// 泛型且没有被实例化：按多态 body 分析
+fn never_instantiated<T: Clone>(v: Vec<T>) -> T {
+    let p = v.as_ptr();
+    drop_vec(v);
+    unsafe { (*p).clone() }  // Use after drop
+}
+
+fn main() {
+    let mut s = Stack::<String>::new();
+    s.push(String::from("a"));
+    let p = s.dangling_top();
+    unsafe {
+        println!("{}", *p);  // Use after drop（Stack::<String>::dangling_top 的实例化）
+    }
+
+    let mut n = Stack::<i32>::new();
+    n.push(1);
+    let q = n.dangling_top();
+    unsafe {
+        println!("{}", *q);  // Use after drop（Stack::<i32>::dangling_top 的实例化）
+    }
+}
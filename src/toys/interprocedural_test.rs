@@ -0,0 +1,49 @@
+//! 集成测试：验证跨函数的 drop/escape 摘要
+//!
+//! 被调函数释放参数或返回指向参数的指针，调用者之后的使用应该被报告
+
+/// 释放参数指向的 Box（摘要：dropped_params = {1}）
+unsafe fn free_box(p: *mut i32) {
+    let _b = Box::from_raw(p);
+}
+
+/// 返回指向参数内部的裸指针（摘要：aliased_params = {1}）
+fn first_ptr(v: &mut Vec<i32>) -> *mut i32 {
+    v.as_mut_ptr()
+}
+
+/// 消费参数（摘要：dropped_params = {1}）
+fn consume(v: Vec<i32>) -> usize {
+    v.len()
+}
+
+fn use_after_callee_free() {
+    let p = Box::into_raw(Box::new(1));
+    unsafe {
+        free_box(p);
+        println!("{}", *p); // Use after drop - free_box 已经释放了 p
+    }
+}
+
+fn use_after_callee_consume() {
+    let mut v = vec![1, 2, 3];
+    let p = first_ptr(&mut v);
+    consume(v);
+    unsafe {
+        println!("{}", *p); // Use after drop - p 指向 v 的缓冲区，v 已被 consume 释放
+    }
+}
+
+fn no_error() {
+    let mut v = vec![1, 2, 3];
+    let p = first_ptr(&mut v);
+    unsafe {
+        println!("{}", *p); // 正常使用
+    }
+}
+
+fn main() {
+    use_after_callee_free();
+    use_after_callee_consume();
+    no_error();
+}
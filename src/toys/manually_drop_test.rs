@@ -0,0 +1,63 @@
+//! ManuallyDrop 测试
+//!
+//! 验证 `ManuallyDrop` 里面的值通过 `DerefMut` 被 drop 时，工具能够：
+//! 1. 把 `&mut *md`（`DerefMut::deref_mut` 的返回值）与 `md` 绑定
+//! 2. 检测 `drop_in_place(&mut *md)` 之后再 `ManuallyDrop::drop(&mut md)` 的 double drop
+//! 3. 不把已经 drop 的 `ManuallyDrop` 报告为内存泄漏（`--detectors leak`）
+//!
+//! 用 `--detectors use-after-drop,leak` 分析时的完整结果只有：
+//! - `test_drop_in_place_then_drop`：`ManuallyDrop::drop(&mut md)` 处的 double-drop
+//! - `test_drop_then_deref`：`md.len()` 处的 use-after-drop
+
+use std::mem::ManuallyDrop;
+use std::ptr;
+
+/// 测试1：通过 DerefMut 就地 drop
+///
+/// 期望：不报错。值已经被 `drop_in_place` drop，不是泄漏
+#[allow(unused)]
+fn test_drop_in_place_deref() {
+    let mut md = ManuallyDrop::new(vec![1, 2, 3]);
+    unsafe {
+        ptr::drop_in_place(&mut *md); // ✓ drop 了 md 里面的 Vec
+    }
+}
+
+/// 测试2：通过 DerefMut 就地 drop 后再调用 ManuallyDrop::drop
+///
+/// 期望：只检测到 double drop。传给 `ManuallyDrop::drop` 的 `&mut md` 不是使用
+#[allow(unused)]
+fn test_drop_in_place_then_drop() {
+    let mut md = ManuallyDrop::new(vec![1, 2, 3]);
+    unsafe {
+        ptr::drop_in_place(&mut *md);
+        ManuallyDrop::drop(&mut md); // ❌ 应该检测到：Double drop
+    }
+}
+
+/// 测试3：通过 Deref 读取 drop 之后的值
+///
+/// 期望：检测到一个 use after drop（`&md` 和 `Deref::deref` 调用的使用位置嵌套，合并为一个结果）
+#[allow(unused)]
+fn test_drop_then_deref() -> usize {
+    let mut md = ManuallyDrop::new(vec![1, 2, 3]);
+    unsafe {
+        ManuallyDrop::drop(&mut md);
+    }
+    md.len() // ❌ 应该检测到：Use after drop
+}
+
+fn main() {
+    println!("=== ManuallyDrop Tests ===");
+    println!("These tests verify drops through DerefMut:");
+    println!("1. ✓ drop_in_place(&mut *md) is not a leak");
+    println!("2. ✓ Detect drop_in_place(&mut *md) followed by ManuallyDrop::drop");
+    println!("3. ✓ Detect use through Deref after ManuallyDrop::drop");
+    println!("");
+
+    // 注意：这些测试函数只是用于生成 MIR 供工具分析
+    // 实际运行会导致未定义行为，所以不调用它们
+    println!("Tests are for static analysis only, not for execution.");
+
+    println!("\n=== Test completed ===");
+}
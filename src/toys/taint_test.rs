@@ -0,0 +1,63 @@
+//! 集成测试：验证 source/sink/sanitizer 污点追踪
+//!
+//! 运行时声明 sanitizer：`TAINT_ANA_SANITIZERS=sanitize_arg`
+
+use std::fs::File;
+use std::io::{BufRead, Read};
+use std::net::TcpStream;
+use std::process::Command;
+
+/// 用户定义的 sanitizer
+fn sanitize_arg(s: &str) -> String {
+    s.chars().filter(|c| c.is_ascii_alphanumeric()).collect()
+}
+
+/// 返回值被污染（摘要：tainted_return）
+fn read_config_path() -> String {
+    std::env::var("CONFIG_PATH").unwrap_or_default()
+}
+
+fn env_to_command() {
+    let user = std::env::var("USER_ARG").unwrap();
+    Command::new("ls").arg(&user).status().ok(); // Tainted: env -> Command::arg
+}
+
+fn stdin_to_file() {
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line).unwrap();
+    let path = line.trim();
+    let _ = File::open(path); // Tainted: stdin -> File::open
+}
+
+fn tcp_to_command(mut stream: TcpStream) {
+    let mut buf = [0u8; 64];
+    let n = stream.read(&mut buf).unwrap();
+    let cmd = String::from_utf8_lossy(&buf[..n]).to_string();
+    Command::new(cmd).status().ok(); // Tainted: TcpStream -> Command::new
+}
+
+fn through_callee() {
+    let path = read_config_path();
+    let _ = File::open(path); // Tainted: 来自被调函数的返回值
+}
+
+fn sanitized() {
+    let user = std::env::var("USER_ARG").unwrap();
+    let clean = sanitize_arg(&user);
+    Command::new("ls").arg(clean).status().ok(); // 正常：经过 sanitizer
+}
+
+fn no_error() {
+    Command::new("ls").arg("-l").status().ok(); // 正常：常量参数
+}
+
+fn main() {
+    env_to_command();
+    stdin_to_file();
+    if let Ok(stream) = TcpStream::connect("127.0.0.1:8080") {
+        tcp_to_command(stream);
+    }
+    through_callee();
+    sanitized();
+    no_error();
+}